@@ -0,0 +1,40 @@
+//! Classify changes between two documents.
+//!
+//! Usage: `l2l-openapi-diff old.json new.json`
+//!
+//! Prints a markdown changelog. Exits with status 1 if there are breaking
+//! changes, and 2 on errors.
+
+use std::process::ExitCode;
+
+use serde_json::Value;
+
+/// Documents are read as JSON, since not all generated schemas can be
+/// deserialized as `utoipa` types
+fn read_doc(path: &str) -> Result<Value, String> {
+    let json =
+        std::fs::read_to_string(path).map_err(|err| format!("failed to read `{path}`: {err}"))?;
+    serde_json::from_str(&json).map_err(|err| format!("failed to parse `{path}`: {err}"))
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let [old_path, new_path] = args.as_slice() else {
+        eprintln!("usage: l2l-openapi-diff <old.json> <new.json>");
+        return ExitCode::from(2);
+    };
+    let (old, new) = match (read_doc(old_path), read_doc(new_path)) {
+        (Ok(old), Ok(new)) => (old, new),
+        (Err(err), _) | (_, Err(err)) => {
+            eprintln!("{err}");
+            return ExitCode::from(2);
+        }
+    };
+    let diff = l2l_openapi::diff_json(&old, &new);
+    print!("{}", diff.to_markdown());
+    if diff.is_breaking() {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
@@ -0,0 +1,293 @@
+//! Call methods on an RPC server, using its document.
+//!
+//! Usage:
+//! * `l2l-rpc [--url <url>] [--spec <file>] methods`: list methods
+//! * `l2l-rpc --url <url> [--spec <file>] call <method> [--param <name>=<value>]..`:
+//!   call a method, and pretty-print the result
+//!
+//! The document is read from `--spec` (OpenRPC or OpenAPI JSON), or fetched
+//! from the server with `rpc.discover`. Param values are parsed as JSON, or
+//! as strings if they are not valid JSON for the param schema, and checked
+//! against the param schemas before calling. Params are sent by name for
+//! methods with `paramStructure: "by-name"`, and by position otherwise.
+//!
+//! Exits with status 1 on error responses, and 2 on other errors.
+
+use std::process::ExitCode;
+
+use jsonrpsee::{
+    core::{
+        client::ClientT,
+        params::{ArrayParams, ObjectParams},
+        ClientError,
+    },
+    http_client::HttpClient,
+};
+use serde_json::Value;
+
+const USAGE: &str = "\
+usage: l2l-rpc [--url <url>] [--spec <file>] methods
+       l2l-rpc --url <url> [--spec <file>] call <method> [--param <name>=<value>]..";
+
+enum Command {
+    Methods,
+    Call {
+        method: String,
+        params: Vec<(String, String)>,
+    },
+}
+
+struct Args {
+    url: Option<String>,
+    spec: Option<String>,
+    command: Command,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let mut url = None;
+    let mut spec = None;
+    let mut positional = Vec::new();
+    let mut params = Vec::new();
+    while let Some(arg) = args.next() {
+        let mut value = |flag: &str| {
+            args.next()
+                .ok_or_else(|| format!("missing value for {flag}"))
+        };
+        match arg.as_str() {
+            "--url" => url = Some(value("--url")?),
+            "--spec" => spec = Some(value("--spec")?),
+            "--param" => {
+                let param = value("--param")?;
+                let Some((name, value)) = param.split_once('=') else {
+                    return Err(format!("expected `<name>=<value>`, found `{param}`"));
+                };
+                params.push((name.to_owned(), value.to_owned()));
+            }
+            flag if flag.starts_with("--") => return Err(format!("unexpected flag: {flag}")),
+            _ => positional.push(arg),
+        }
+    }
+    let command = match positional.as_slice() {
+        [command] if command == "methods" && params.is_empty() => Command::Methods,
+        [command, method] if command == "call" => Command::Call {
+            method: method.clone(),
+            params,
+        },
+        _ => return Err(USAGE.to_owned()),
+    };
+    Ok(Args { url, spec, command })
+}
+
+/// Read a document from a file, converting OpenAPI documents to OpenRPC
+fn read_spec(path: &str) -> Result<Value, String> {
+    let json =
+        std::fs::read_to_string(path).map_err(|err| format!("failed to read `{path}`: {err}"))?;
+    let doc: Value =
+        serde_json::from_str(&json).map_err(|err| format!("failed to parse `{path}`: {err}"))?;
+    if doc.get("openrpc").is_some() {
+        Ok(doc)
+    } else if doc.get("openapi").is_some() {
+        Ok(l2l_openapi::openrpc_json(&doc))
+    } else {
+        Err(format!("`{path}` is not an OpenRPC or OpenAPI document"))
+    }
+}
+
+/// Methods in an OpenRPC document
+fn methods(doc: &Value) -> &[Value] {
+    doc.get("methods")
+        .and_then(Value::as_array)
+        .map_or(&[], Vec::as_slice)
+}
+
+fn str_field<'a>(value: &'a Value, key: &str) -> &'a str {
+    value.get(key).and_then(Value::as_str).unwrap_or_default()
+}
+
+fn print_methods(doc: &Value) {
+    for method in methods(doc) {
+        let params: Vec<String> = method
+            .get("params")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .map(|param| {
+                let optional = if param.get("required") == Some(&Value::Bool(false)) {
+                    "?"
+                } else {
+                    ""
+                };
+                format!("{}{optional}", str_field(param, "name"))
+            })
+            .collect();
+        println!("{}({})", str_field(method, "name"), params.join(", "));
+        for line in str_field(method, "description").lines() {
+            println!("    {line}");
+        }
+    }
+}
+
+/// Find a method by name or alias, returning its index and method object
+fn find_method<'a>(doc: &'a Value, name: &str) -> Option<(usize, &'a Value)> {
+    methods(doc).iter().enumerate().find(|(_, method)| {
+        str_field(method, "name") == name
+            || method
+                .get("x-aliases")
+                .and_then(Value::as_array)
+                .is_some_and(|aliases| aliases.iter().any(|alias| alias == name))
+    })
+}
+
+/// Parse a param value as JSON, or as a string if the JSON value does not
+/// match the schema
+fn parse_param(doc: &Value, schema_path: &str, name: &str, value: &str) -> Result<Value, String> {
+    let json = serde_json::from_str::<Value>(value).ok();
+    let errs = match json {
+        Some(json) => match l2l_openapi::validate_value(doc, schema_path, &json) {
+            Ok(()) => return Ok(json),
+            Err(errs) => errs,
+        },
+        None => Vec::new(),
+    };
+    let string = Value::String(value.to_owned());
+    match l2l_openapi::validate_value(doc, schema_path, &string) {
+        Ok(()) => Ok(string),
+        Err(string_errs) => {
+            let errs = if errs.is_empty() { string_errs } else { errs };
+            let mut err_msg = format!("invalid value for param `{name}`:");
+            for err in errs {
+                err_msg.push_str(&format!("\n  {err}"));
+            }
+            Err(err_msg)
+        }
+    }
+}
+
+/// Params for a method, from named param values.
+/// Omitted optional params are `None`.
+fn method_params<'a>(
+    doc: &Value,
+    method_idx: usize,
+    method: &'a Value,
+    mut values: Vec<(String, String)>,
+) -> Result<Vec<(&'a str, Option<Value>)>, String> {
+    let method_params = method
+        .get("params")
+        .and_then(Value::as_array)
+        .map_or(&[][..], Vec::as_slice);
+    let mut params = Vec::new();
+    for (param_idx, param) in method_params.iter().enumerate() {
+        let name = str_field(param, "name");
+        let value = match values.iter().position(|(value_name, _)| value_name == name) {
+            Some(value_idx) => values.remove(value_idx).1,
+            None if param.get("required") == Some(&Value::Bool(false)) => {
+                params.push((name, None));
+                continue;
+            }
+            None => return Err(format!("missing required param `{name}`")),
+        };
+        let schema_path = format!("/methods/{method_idx}/params/{param_idx}/schema");
+        params.push((name, Some(parse_param(doc, &schema_path, name, &value)?)));
+    }
+    if let Some((name, _)) = values.first() {
+        let names: Vec<&str> = method_params
+            .iter()
+            .map(|param| str_field(param, "name"))
+            .collect();
+        return Err(format!(
+            "unknown param `{name}`; expected one of: {}",
+            names.join(", ")
+        ));
+    }
+    Ok(params)
+}
+
+/// Call a method, with params by name or by position
+async fn call(
+    client: &HttpClient,
+    method: &str,
+    method_obj: &Value,
+    mut params: Vec<(&str, Option<Value>)>,
+) -> Result<Value, ClientError> {
+    if method_obj.get("paramStructure") == Some(&Value::from("by-name")) {
+        let mut object_params = ObjectParams::new();
+        for (name, param) in params {
+            if let Some(param) = param {
+                object_params.insert(name, param)?;
+            }
+        }
+        return client.request(method, object_params).await;
+    }
+    // Omitted positional params are trailing optional params
+    while let Some((_, None)) = params.last() {
+        params.pop();
+    }
+    let mut array_params = ArrayParams::new();
+    for (_, param) in params {
+        array_params.insert(param.unwrap_or_default())?;
+    }
+    client.request(method, array_params).await
+}
+
+async fn run(args: Args) -> Result<ExitCode, String> {
+    let client = args
+        .url
+        .as_ref()
+        .map(|url| {
+            HttpClient::builder()
+                .build(url)
+                .map_err(|err| format!("invalid url `{url}`: {err}"))
+        })
+        .transpose()?;
+    let doc = match (&args.spec, &client) {
+        (Some(spec), _) => read_spec(spec)?,
+        (None, Some(client)) => client
+            .request::<Value, _>(l2l_openapi::DISCOVER_METHOD, ArrayParams::new())
+            .await
+            .map_err(|err| format!("failed to fetch document: {err}"))?,
+        (None, None) => return Err("either --url or --spec is required".to_owned()),
+    };
+    let (method, values) = match args.command {
+        Command::Methods => {
+            print_methods(&doc);
+            return Ok(ExitCode::SUCCESS);
+        }
+        Command::Call { method, params } => (method, params),
+    };
+    let Some(client) = client else {
+        return Err("--url is required to call methods".to_owned());
+    };
+    let Some((method_idx, method_obj)) = find_method(&doc, &method) else {
+        return Err(format!("unknown method `{method}`"));
+    };
+    let params = method_params(&doc, method_idx, method_obj, values)?;
+    match call(&client, &method, method_obj, params).await {
+        Ok(res) => {
+            let res = serde_json::to_string_pretty(&res).expect("JSON should serialize");
+            println!("{res}");
+            Ok(ExitCode::SUCCESS)
+        }
+        Err(ClientError::Call(err)) => {
+            eprintln!("error {}: {}", err.code(), err.message());
+            if let Some(data) = err.data() {
+                let data: Value = serde_json::from_str(data.get()).unwrap_or_default();
+                let data = serde_json::to_string_pretty(&data).expect("JSON should serialize");
+                eprintln!("{data}");
+            }
+            Ok(ExitCode::FAILURE)
+        }
+        Err(err) => Err(format!("request failed: {err}")),
+    }
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let res = match parse_args(std::env::args().skip(1)) {
+        Ok(args) => run(args).await,
+        Err(err) => Err(err),
+    };
+    res.unwrap_or_else(|err| {
+        eprintln!("{err}");
+        ExitCode::from(2)
+    })
+}
@@ -0,0 +1,208 @@
+use std::process::Output;
+
+use jsonrpsee::{
+    core::RpcResult,
+    proc_macros::rpc,
+    server::{Server, ServerHandle},
+};
+use l2l_openapi::{discover_module, open_api};
+use serde::Serialize;
+use utoipa::{OpenApi, ToSchema};
+
+#[derive(Clone, Serialize, ToSchema)]
+pub struct Header {
+    pub hash: String,
+    pub height: u32,
+}
+
+#[open_api(ref_schemas [Header])]
+#[rpc(server)]
+pub trait Chain {
+    /// Get headers, starting at a height
+    #[method(name = "get_headers", aliases = ["getheaders"])]
+    async fn get_headers(&self, start: u32, count: Option<u32>) -> RpcResult<Vec<Header>>;
+
+    /// Get a block hash
+    #[method(name = "get_block_hash")]
+    async fn get_block_hash(&self, height: u32) -> RpcResult<String>;
+
+    #[method(name = "set_label")]
+    async fn set_label(&self, label: String) -> RpcResult<String>;
+
+    #[method(name = "add_peer", param_kind = map)]
+    async fn add_peer(
+        &self,
+        host: String,
+        port: Option<u16>,
+        label: Option<String>,
+    ) -> RpcResult<String>;
+}
+
+struct ChainImpl;
+
+#[jsonrpsee::core::async_trait]
+impl ChainServer for ChainImpl {
+    async fn get_headers(&self, start: u32, count: Option<u32>) -> RpcResult<Vec<Header>> {
+        let headers = (start..start + count.unwrap_or(1))
+            .map(|height| Header {
+                hash: format!("{height:02}"),
+                height,
+            })
+            .collect();
+        Ok(headers)
+    }
+
+    async fn get_block_hash(&self, height: u32) -> RpcResult<String> {
+        Err(jsonrpsee::types::ErrorObject::owned(
+            -5,
+            "block not found",
+            Some(height),
+        ))
+    }
+
+    async fn set_label(&self, label: String) -> RpcResult<String> {
+        Ok(label)
+    }
+
+    async fn add_peer(
+        &self,
+        host: String,
+        port: Option<u16>,
+        label: Option<String>,
+    ) -> RpcResult<String> {
+        Ok(format!(
+            "{host}:{} ({})",
+            port.unwrap_or(8000),
+            label.unwrap_or_default()
+        ))
+    }
+}
+
+async fn server() -> anyhow::Result<(String, ServerHandle)> {
+    let server = Server::builder().build("127.0.0.1:0").await?;
+    let url = format!("http://{}", server.local_addr()?);
+    let mut module = ChainImpl.into_rpc();
+    module.merge(discover_module(&ChainDoc::openapi()))?;
+    Ok((url, server.start(module)))
+}
+
+async fn l2l_rpc(args: &[&str]) -> anyhow::Result<Output> {
+    let output = tokio::process::Command::new(env!("CARGO_BIN_EXE_l2l-rpc"))
+        .args(args)
+        .output()
+        .await?;
+    Ok(output)
+}
+
+#[tokio::test]
+async fn test_methods() -> anyhow::Result<()> {
+    let (url, handle) = server().await?;
+    let output = l2l_rpc(&["--url", &url, "methods"]).await?;
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        "\
+add_peer(host, port?, label?)
+get_block_hash(height)
+    Get a block hash
+get_headers(start, count?)
+    Get headers, starting at a height
+set_label(label)
+"
+    );
+    handle.stop()?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_call() -> anyhow::Result<()> {
+    let (url, handle) = server().await?;
+    let output = l2l_rpc(&[
+        "--url",
+        &url,
+        "call",
+        "getheaders",
+        "--param",
+        "count=2",
+        "--param",
+        "start=3",
+    ])
+    .await?;
+    assert!(output.status.success());
+    let res: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(
+        res,
+        serde_json::json!([
+            { "hash": "03", "height": 3 },
+            { "hash": "04", "height": 4 },
+        ])
+    );
+    // Values that are not valid JSON for the schema are strings
+    let output = l2l_rpc(&["--url", &url, "call", "set_label", "--param", "label=42"]).await?;
+    assert_eq!(String::from_utf8(output.stdout)?, "\"42\"\n");
+    // Params are sent by name for by-name methods
+    let output = l2l_rpc(&[
+        "--url",
+        &url,
+        "call",
+        "add_peer",
+        "--param",
+        "label=seed",
+        "--param",
+        "host=node",
+    ])
+    .await?;
+    assert_eq!(String::from_utf8(output.stdout)?, "\"node:8000 (seed)\"\n");
+    // Error responses
+    let output = l2l_rpc(&[
+        "--url",
+        &url,
+        "call",
+        "get_block_hash",
+        "--param",
+        "height=9",
+    ])
+    .await?;
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8(output.stderr)?,
+        "error -5: block not found\n9\n"
+    );
+    handle.stop()?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_call_invalid_params() -> anyhow::Result<()> {
+    let (url, handle) = server().await?;
+    let stderr = |output: Output| String::from_utf8(output.stderr);
+    let output = l2l_rpc(&["--url", &url, "call", "get_headers", "--param", "start=-1"]).await?;
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(output)?.starts_with("invalid value for param `start`:\n  /: "));
+    let output = l2l_rpc(&["--url", &url, "call", "get_headers"]).await?;
+    assert_eq!(stderr(output)?, "missing required param `start`\n");
+    let output = l2l_rpc(&["--url", &url, "call", "get_headers", "--param", "begin=0"]).await?;
+    assert_eq!(stderr(output)?, "missing required param `start`\n");
+    let spec_path = std::env::temp_dir().join(format!("l2l-rpc-{}.json", std::process::id()));
+    std::fs::write(&spec_path, ChainDoc::openapi().to_json()?)?;
+    let output = l2l_rpc(&[
+        "--spec",
+        spec_path.to_str().unwrap(),
+        "--url",
+        &url,
+        "call",
+        "get_headers",
+        "--param",
+        "start=0",
+        "--param",
+        "end=1",
+    ])
+    .await?;
+    assert_eq!(
+        stderr(output)?,
+        "unknown param `end`; expected one of: start, count\n"
+    );
+    std::fs::remove_file(spec_path)?;
+    handle.stop()?;
+    Ok(())
+}
@@ -0,0 +1,61 @@
+use std::path::Path;
+
+use utoipa::OpenApi;
+
+mod v1 {
+    use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+    use l2l_openapi::open_api;
+
+    #[open_api]
+    #[rpc(server)]
+    pub trait Node {
+        #[method(name = "get_block_hash")]
+        async fn get_block_hash(&self, height: u32) -> RpcResult<String>;
+
+        #[method(name = "stop")]
+        async fn stop(&self) -> RpcResult<()>;
+    }
+}
+
+mod v2 {
+    use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+    use l2l_openapi::open_api;
+
+    #[open_api]
+    #[rpc(server)]
+    pub trait Node {
+        #[method(name = "get_block_hash")]
+        async fn get_block_hash(&self, height: u32) -> RpcResult<String>;
+
+        #[method(name = "shutdown")]
+        async fn shutdown(&self) -> RpcResult<()>;
+    }
+}
+
+fn l2l_openapi_diff(old_path: &Path, new_path: &Path) -> std::io::Result<std::process::Output> {
+    std::process::Command::new(env!("CARGO_BIN_EXE_l2l-openapi-diff"))
+        .args([old_path, new_path])
+        .output()
+}
+
+#[test]
+fn test_diff() -> anyhow::Result<()> {
+    let dir = std::env::temp_dir().join(format!("l2l-openapi-diff-{}", std::process::id()));
+    std::fs::create_dir_all(&dir)?;
+    let old_path = dir.join("old.json");
+    let new_path = dir.join("new.json");
+    std::fs::write(&old_path, v1::NodeDoc::openapi().to_json()?)?;
+    std::fs::write(&new_path, v2::NodeDoc::openapi().to_json()?)?;
+    let output = l2l_openapi_diff(&old_path, &new_path)?;
+    assert_eq!(output.status.code(), Some(1));
+    let changelog = String::from_utf8(output.stdout)?;
+    assert!(changelog.contains("## Breaking changes\n\n- removed method `stop`\n"));
+    let output = l2l_openapi_diff(&old_path, &old_path)?;
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        "# API changes\n\nNo changes.\n"
+    );
+    std::fs::remove_dir_all(dir)?;
+    Ok(())
+}
@@ -0,0 +1,34 @@
+//! Method audiences, set with `#[open_api_method(audience = "admin")]`
+
+use utoipa::openapi::{path::Operation, OpenApi};
+
+use crate::util::retain_operations;
+
+#[cfg(feature = "middleware")]
+mod middleware;
+
+#[cfg(feature = "middleware")]
+pub use middleware::{AudienceFilter, AudienceFilterLayer};
+
+/// Audience of methods that do not set an audience
+pub const DEFAULT_AUDIENCE: &str = "public";
+
+/// Audience of an operation, from the `x-audience` extension
+fn operation_audience(operation: &Operation) -> &str {
+    operation
+        .extensions
+        .as_ref()
+        .and_then(|extensions| extensions.get("x-audience"))
+        .and_then(|audience| audience.as_str())
+        .unwrap_or(DEFAULT_AUDIENCE)
+}
+
+/// Remove operations that are not for one of the audiences, and path items
+/// that have no remaining operations.
+/// Generated as `{Trait}Doc::openapi_for(audiences)`.
+pub fn filter_audiences(mut openapi: OpenApi, audiences: &[&str]) -> OpenApi {
+    retain_operations(&mut openapi, |operation| {
+        audiences.contains(&operation_audience(operation))
+    });
+    openapi
+}
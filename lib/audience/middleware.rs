@@ -0,0 +1,137 @@
+//! RPC middleware that filters calls by audience
+
+use std::{collections::HashSet, future::Future, sync::Arc};
+
+use jsonrpsee::{
+    core::middleware::{Batch, BatchEntry, BatchEntryErr, Notification, RpcServiceT},
+    types::{ErrorCode, ErrorObject, Request},
+    MethodResponse,
+};
+use utoipa::openapi::OpenApi;
+
+use super::operation_audience;
+use crate::util::{extension_strs, operations};
+
+#[derive(Clone, Debug)]
+struct Inner {
+    /// Names of methods that are for an allowed audience, including aliases
+    allowed: HashSet<String>,
+    /// Names of documented methods, including aliases
+    documented: HashSet<String>,
+    /// Allow calls to methods that are not documented
+    allow_undocumented: bool,
+}
+
+impl Inner {
+    fn check(&self, request: &Request<'_>) -> Result<(), ErrorObject<'static>> {
+        let method = request.method_name();
+        if self.allowed.contains(method)
+            || (self.allow_undocumented && !self.documented.contains(method))
+        {
+            Ok(())
+        } else {
+            Err(ErrorCode::MethodNotFound.into())
+        }
+    }
+}
+
+/// Layer for [`AudienceFilter`]
+#[derive(Clone, Debug)]
+pub struct AudienceFilterLayer(Arc<Inner>);
+
+impl AudienceFilterLayer {
+    /// Only allow calls to methods in a document that are for one of the
+    /// audiences
+    pub fn new(openapi: &OpenApi, audiences: &[&str]) -> Self {
+        let mut allowed = HashSet::new();
+        let mut documented = HashSet::new();
+        for (path, operation) in operations(openapi) {
+            let aliases = extension_strs(operation, "x-aliases").unwrap_or_default();
+            let names = std::iter::once(path).chain(aliases).map(str::to_owned);
+            if audiences.contains(&operation_audience(operation)) {
+                allowed.extend(names.clone());
+            }
+            documented.extend(names);
+        }
+        Self(Arc::new(Inner {
+            allowed,
+            documented,
+            allow_undocumented: false,
+        }))
+    }
+
+    /// Also allow calls to methods that are not in the document, eg. methods
+    /// from RPC modules without docs
+    pub fn allow_undocumented(mut self) -> Self {
+        Arc::make_mut(&mut self.0).allow_undocumented = true;
+        self
+    }
+}
+
+impl<S> tower::Layer<S> for AudienceFilterLayer {
+    type Service = AudienceFilter<S>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        AudienceFilter {
+            inner: self.0.clone(),
+            service,
+        }
+    }
+}
+
+/// RPC middleware that rejects calls to methods that are not for an allowed
+/// audience, with a `Method not found` error.
+/// Methods that are not documented, eg. methods registered with
+/// `RpcModule::register_method` or `rpc.discover`, are also rejected, unless
+/// [`AudienceFilterLayer::allow_undocumented`] is set. Document them with
+/// [`crate::DocBuilder`] and [`crate::merge_all`] to allow them.
+/// Notifications are not rejected.
+#[derive(Clone, Debug)]
+pub struct AudienceFilter<S> {
+    inner: Arc<Inner>,
+    service: S,
+}
+
+impl<S> RpcServiceT for AudienceFilter<S>
+where
+    S: RpcServiceT<MethodResponse = MethodResponse> + Send + Sync + Clone + 'static,
+{
+    type MethodResponse = S::MethodResponse;
+    type NotificationResponse = S::NotificationResponse;
+    type BatchResponse = S::BatchResponse;
+
+    fn call<'a>(
+        &self,
+        request: Request<'a>,
+    ) -> impl Future<Output = Self::MethodResponse> + Send + 'a {
+        let res = self.inner.check(&request);
+        let service = self.service.clone();
+        async move {
+            match res {
+                Ok(()) => service.call(request).await,
+                Err(err) => MethodResponse::error(request.id(), err),
+            }
+        }
+    }
+
+    fn batch<'a>(
+        &self,
+        mut requests: Batch<'a>,
+    ) -> impl Future<Output = Self::BatchResponse> + Send + 'a {
+        for entry in requests.iter_mut() {
+            if let Ok(BatchEntry::Call(request)) = entry {
+                if let Err(err) = self.inner.check(request) {
+                    *entry = Err(BatchEntryErr::new(request.id(), err));
+                }
+            }
+        }
+        self.service.batch(requests)
+    }
+
+    fn notification<'a>(
+        &self,
+        n: Notification<'a>,
+    ) -> impl Future<Output = Self::NotificationResponse> + Send + 'a {
+        self.service.notification(n)
+    }
+}
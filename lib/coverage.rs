@@ -0,0 +1,74 @@
+//! Check that a document covers the methods registered in an RPC module
+
+use std::collections::BTreeSet;
+
+use jsonrpsee::{MethodCallback, Methods};
+use utoipa::openapi::OpenApi;
+
+use crate::util::{extension_strs, operations};
+
+/// Differences between the methods registered in an RPC module, and the
+/// methods in a document
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CoverageError {
+    /// Methods that are registered, but not documented
+    pub undocumented: BTreeSet<String>,
+    /// Methods that are documented, but not registered
+    pub unregistered: BTreeSet<String>,
+}
+
+impl std::fmt::Display for CoverageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "document does not match RPC module")?;
+        if !self.undocumented.is_empty() {
+            let undocumented = Vec::from_iter(self.undocumented.iter().map(String::as_str));
+            write!(f, "\n  undocumented methods: {}", undocumented.join(", "))?;
+        }
+        if !self.unregistered.is_empty() {
+            let unregistered = Vec::from_iter(self.unregistered.iter().map(String::as_str));
+            write!(f, "\n  unregistered methods: {}", unregistered.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for CoverageError {}
+
+/// Method names in a document. Paths are method names, and aliases are
+/// listed in the `x-aliases` extension of an operation.
+fn documented_methods(openapi: &OpenApi) -> BTreeSet<String> {
+    let mut res = BTreeSet::new();
+    for (path, operation) in operations(openapi) {
+        res.insert(path.to_owned());
+        let aliases = extension_strs(operation, "x-aliases").unwrap_or_default();
+        res.extend(aliases.into_iter().map(str::to_owned));
+    }
+    res
+}
+
+/// Check that the methods registered in an RPC module match the methods in a
+/// document.
+/// Subscriptions are not documented, so subscription and unsubscription
+/// methods are ignored.
+pub fn check_coverage(methods: &Methods, openapi: &OpenApi) -> Result<(), CoverageError> {
+    let registered: BTreeSet<String> = methods
+        .method_names()
+        .filter(|name| {
+            !matches!(
+                methods.method(name),
+                Some(MethodCallback::Subscription(_) | MethodCallback::Unsubscription(_))
+            )
+        })
+        .map(str::to_owned)
+        .collect();
+    let documented = documented_methods(openapi);
+    let res = CoverageError {
+        undocumented: registered.difference(&documented).cloned().collect(),
+        unregistered: documented.difference(&registered).cloned().collect(),
+    };
+    if res == CoverageError::default() {
+        Ok(())
+    } else {
+        Err(res)
+    }
+}
@@ -0,0 +1,650 @@
+//! Classify changes between two versions of a document
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+use serde_json::{Map, Value};
+use utoipa::openapi::OpenApi;
+
+use crate::{
+    json_schema::{integer_format_range, Validator},
+    util::{
+        escape_pointer_segment, json_extension_strs, json_operations, json_param_names,
+        param_schema_paths,
+    },
+};
+
+/// Relation between the sets of values accepted by an old and a new schema
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Compat {
+    /// Accept the same values
+    Equal,
+    /// The new schema accepts more values
+    Wider,
+    /// The new schema accepts fewer values
+    Narrower,
+    /// Each schema accepts values that the other does not
+    Incompatible,
+}
+
+impl Compat {
+    /// Combine relations for different constraints of the same schema
+    fn and(self, other: Self) -> Self {
+        match (self, other) {
+            (Self::Equal, compat) | (compat, Self::Equal) => compat,
+            (lhs, rhs) if lhs == rhs => lhs,
+            _ => Self::Incompatible,
+        }
+    }
+
+    /// Relation between two sets, where `None` is unrestricted
+    fn of_sets<T: Ord>(old: Option<&BTreeSet<T>>, new: Option<&BTreeSet<T>>) -> Self {
+        match (old, new) {
+            (None, None) => Self::Equal,
+            (Some(_), None) => Self::Wider,
+            (None, Some(_)) => Self::Narrower,
+            (Some(old), Some(new)) if old == new => Self::Equal,
+            (Some(old), Some(new)) if new.is_superset(old) => Self::Wider,
+            (Some(old), Some(new)) if new.is_subset(old) => Self::Narrower,
+            (Some(_), Some(_)) => Self::Incompatible,
+        }
+    }
+
+    /// Relation between two inclusive ranges
+    fn of_ranges<T: PartialOrd>(old: (T, T), new: (T, T)) -> Self {
+        let contains = |outer: &(T, T), inner: &(T, T)| outer.0 <= inner.0 && inner.1 <= outer.1;
+        match (contains(&new, &old), contains(&old, &new)) {
+            (true, true) => Self::Equal,
+            (true, false) => Self::Wider,
+            (false, true) => Self::Narrower,
+            (false, false) => Self::Incompatible,
+        }
+    }
+}
+
+impl std::fmt::Display for Compat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Equal => write!(f, "unchanged"),
+            Self::Wider => write!(f, "widened"),
+            Self::Narrower => write!(f, "narrowed"),
+            Self::Incompatible => write!(f, "changed incompatibly"),
+        }
+    }
+}
+
+/// Change to a method between two versions of a document
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Change {
+    MethodAdded(String),
+    MethodRemoved(String),
+    ParamAdded {
+        method: String,
+        param: String,
+        /// Params that do not accept `null` are required
+        required: bool,
+    },
+    ParamRemoved {
+        method: String,
+        param: String,
+    },
+    /// A param is at a different position
+    ParamMoved {
+        method: String,
+        param: String,
+        old_idx: usize,
+        new_idx: usize,
+    },
+    ParamChanged {
+        method: String,
+        param: String,
+        compat: Compat,
+    },
+    ResultChanged {
+        method: String,
+        compat: Compat,
+    },
+}
+
+impl Change {
+    /// Returns true if clients of the old version may not work with the new
+    /// version
+    pub fn is_breaking(&self) -> bool {
+        match self {
+            Self::MethodAdded(_) => false,
+            Self::MethodRemoved(_) => true,
+            Self::ParamAdded { required, .. } => *required,
+            Self::ParamRemoved { .. } | Self::ParamMoved { .. } => true,
+            Self::ParamChanged { compat, .. } => {
+                matches!(compat, Compat::Narrower | Compat::Incompatible)
+            }
+            Self::ResultChanged { compat, .. } => {
+                matches!(compat, Compat::Wider | Compat::Incompatible)
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Change {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MethodAdded(method) => write!(f, "added method `{method}`"),
+            Self::MethodRemoved(method) => write!(f, "removed method `{method}`"),
+            Self::ParamAdded {
+                method,
+                param,
+                required,
+            } => {
+                let kind = if *required { "required" } else { "optional" };
+                write!(f, "`{method}`: added {kind} param `{param}`")
+            }
+            Self::ParamRemoved { method, param } => {
+                write!(f, "`{method}`: removed param `{param}`")
+            }
+            Self::ParamMoved {
+                method,
+                param,
+                old_idx,
+                new_idx,
+            } => write!(
+                f,
+                "`{method}`: moved param `{param}` from position {old_idx} to {new_idx}"
+            ),
+            Self::ParamChanged {
+                method,
+                param,
+                compat,
+            } => write!(f, "`{method}`: {compat} type of param `{param}`"),
+            Self::ResultChanged { method, compat } => {
+                write!(f, "`{method}`: {compat} result type")
+            }
+        }
+    }
+}
+
+/// Changes between two versions of a document
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Diff(pub Vec<Change>);
+
+impl Diff {
+    /// Returns true if any change is breaking
+    pub fn is_breaking(&self) -> bool {
+        self.0.iter().any(Change::is_breaking)
+    }
+
+    /// Changelog, with breaking and non-breaking changes in separate sections
+    pub fn to_markdown(&self) -> String {
+        let mut res = String::from("# API changes\n");
+        if self.0.is_empty() {
+            res.push_str("\nNo changes.\n");
+            return res;
+        }
+        let (breaking, non_breaking): (Vec<_>, Vec<_>) =
+            self.0.iter().partition(|change| change.is_breaking());
+        for (heading, changes) in [
+            ("Breaking changes", breaking),
+            ("Non-breaking changes", non_breaking),
+        ] {
+            if changes.is_empty() {
+                continue;
+            }
+            res.push_str(&format!("\n## {heading}\n\n"));
+            for change in changes {
+                res.push_str(&format!("- {change}\n"));
+            }
+        }
+        res
+    }
+}
+
+/// Keywords that do not affect which values a schema accepts
+const ANNOTATION_KEYWORDS: &[&str] = &[
+    "$schema",
+    "default",
+    "deprecated",
+    "description",
+    "example",
+    "examples",
+    "readOnly",
+    "title",
+    "writeOnly",
+];
+
+fn contains_ref(schema: &Value) -> bool {
+    match schema {
+        Value::Array(items) => items.iter().any(contains_ref),
+        Value::Object(map) => map.contains_key("$ref") || map.values().any(contains_ref),
+        _ => false,
+    }
+}
+
+/// Compares schemas from two documents
+struct Comparer<'a> {
+    old: Validator<'a>,
+    new: Validator<'a>,
+    /// Pairs of references that are being compared, to handle recursive
+    /// schemas
+    visiting: HashSet<(String, String)>,
+}
+
+impl Comparer<'_> {
+    /// Split a schema into variants
+    fn variants(schema: &Value, depth: usize) -> Vec<Value> {
+        let Value::Object(map) = schema else {
+            return vec![schema.clone()];
+        };
+        if depth > 32 {
+            return vec![schema.clone()];
+        }
+        // Union keywords without other constraints
+        let is_union = |keyword: &str| {
+            map.contains_key(keyword)
+                && map
+                    .keys()
+                    .all(|key| key == keyword || ANNOTATION_KEYWORDS.contains(&key.as_str()))
+        };
+        for keyword in ["oneOf", "anyOf"] {
+            if is_union(keyword) {
+                return map[keyword]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .flat_map(|variant| Self::variants(variant, depth + 1))
+                    .collect();
+            }
+        }
+        // Single `allOf`, as used for references with descriptions
+        if is_union("allOf") {
+            if let Some([subschema]) = map["allOf"].as_array().map(Vec::as_slice) {
+                return Self::variants(subschema, depth + 1);
+            }
+        }
+        if let Some(Value::Array(tys)) = map.get("type") {
+            return tys
+                .iter()
+                .map(|ty| {
+                    let mut map = map.clone();
+                    map.insert("type".to_owned(), ty.clone());
+                    Value::Object(map)
+                })
+                .collect();
+        }
+        vec![schema.clone()]
+    }
+
+    /// Compare an old and a new schema
+    fn compare(&mut self, old: &Value, new: &Value) -> Compat {
+        // References may resolve to different schemas
+        if old == new && !contains_ref(old) {
+            return Compat::Equal;
+        }
+        let old_ref = old.get("$ref").and_then(Value::as_str);
+        let new_ref = new.get("$ref").and_then(Value::as_str);
+        if old_ref.is_some() || new_ref.is_some() {
+            let key = (
+                old_ref.unwrap_or_default().to_owned(),
+                new_ref.unwrap_or_default().to_owned(),
+            );
+            if !self.visiting.insert(key.clone()) {
+                return Compat::Equal;
+            }
+            let resolve = |validator: &Validator<'_>, schema: &Value, reference: Option<&str>| {
+                match reference {
+                    Some(reference) => validator.resolve(reference).cloned(),
+                    None => Some(schema.clone()),
+                }
+            };
+            let res = match (
+                resolve(&self.old, old, old_ref),
+                resolve(&self.new, new, new_ref),
+            ) {
+                (Some(old), Some(new)) => self.compare(&old, &new),
+                _ => Compat::Incompatible,
+            };
+            self.visiting.remove(&key);
+            return res;
+        }
+        let old_variants = Self::variants(old, 0);
+        let new_variants = Self::variants(new, 0);
+        if let ([old], [new]) = (old_variants.as_slice(), new_variants.as_slice()) {
+            if old.get("$ref").is_none() && new.get("$ref").is_none() {
+                return self.compare_single(old, new);
+            }
+        }
+        let old_covered = old_variants.iter().all(|old| {
+            new_variants
+                .iter()
+                .any(|new| matches!(self.compare(old, new), Compat::Equal | Compat::Wider))
+        });
+        let new_covered = new_variants.iter().all(|new| {
+            old_variants
+                .iter()
+                .any(|old| matches!(self.compare(old, new), Compat::Equal | Compat::Narrower))
+        });
+        match (old_covered, new_covered) {
+            (true, true) => Compat::Equal,
+            (true, false) => Compat::Wider,
+            (false, true) => Compat::Narrower,
+            (false, false) => Compat::Incompatible,
+        }
+    }
+
+    /// Compare schemas that are not unions or references
+    fn compare_single(&mut self, old: &Value, new: &Value) -> Compat {
+        let empty = Map::new();
+        let schema_map = |schema: &Value| -> Option<Map<String, Value>> {
+            match schema {
+                Value::Bool(true) => Some(Map::new()),
+                Value::Object(map) => Some(map.clone()),
+                _ => None,
+            }
+        };
+        let (old, new) = match (schema_map(old), schema_map(new)) {
+            (Some(old), Some(new)) => (old, new),
+            (None, None) => return Compat::Equal,
+            (None, Some(_)) => return Compat::Wider,
+            (Some(_), None) => return Compat::Narrower,
+        };
+        let (old, new) = (&old, &new);
+        let mut res = Compat::Equal;
+
+        // Types
+        let old_ty = old.get("type").and_then(Value::as_str);
+        let new_ty = new.get("type").and_then(Value::as_str);
+        res = res.and(match (old_ty, new_ty) {
+            (None, None) => Compat::Equal,
+            (Some(old_ty), Some(new_ty)) if old_ty == new_ty => Compat::Equal,
+            (Some(_), None) | (Some("integer"), Some("number")) => Compat::Wider,
+            (None, Some(_)) | (Some("number"), Some("integer")) => Compat::Narrower,
+            (Some(_), Some(_)) => return Compat::Incompatible,
+        });
+
+        // Enumerated values
+        let values = |map: &Map<String, Value>| -> Option<BTreeSet<String>> {
+            match (map.get("enum"), map.get("const")) {
+                (Some(Value::Array(variants)), _) => {
+                    Some(variants.iter().map(Value::to_string).collect())
+                }
+                (_, Some(value)) => Some(BTreeSet::from([value.to_string()])),
+                _ => None,
+            }
+        };
+        res = res.and(Compat::of_sets(values(old).as_ref(), values(new).as_ref()));
+
+        // Numeric bounds, as `(bound, exclusive)` for minimums and
+        // `(bound, inclusive)` for maximums, so that tighter bounds compare
+        // greater and less respectively
+        let number_range = |map: &Map<String, Value>| {
+            let bound = |keyword: &str| map.get(keyword).and_then(Value::as_f64);
+            // OpenAPI 3.0 documents use boolean exclusive bounds
+            let exclusive = |keyword: &str| map.get(keyword) == Some(&Value::Bool(true));
+            let (min, max) = map
+                .get("format")
+                .and_then(Value::as_str)
+                .and_then(integer_format_range)
+                .map_or((f64::NEG_INFINITY, f64::INFINITY), |(min, max)| {
+                    (min as f64, max as f64)
+                });
+            let mut min = (min, false);
+            let mut max = (max, true);
+            if let Some(minimum) = bound("minimum") {
+                let minimum = (minimum, exclusive("exclusiveMinimum"));
+                if minimum > min {
+                    min = minimum;
+                }
+            }
+            if let Some(exclusive_minimum) = bound("exclusiveMinimum") {
+                if (exclusive_minimum, true) > min {
+                    min = (exclusive_minimum, true);
+                }
+            }
+            if let Some(maximum) = bound("maximum") {
+                let maximum = (maximum, !exclusive("exclusiveMaximum"));
+                if maximum < max {
+                    max = maximum;
+                }
+            }
+            if let Some(exclusive_maximum) = bound("exclusiveMaximum") {
+                if (exclusive_maximum, false) < max {
+                    max = (exclusive_maximum, false);
+                }
+            }
+            (min, max)
+        };
+        res = res.and(Compat::of_ranges(number_range(old), number_range(new)));
+
+        // String and array lengths
+        let length_range = |map: &Map<String, Value>, min: &str, max: &str| {
+            let min = map.get(min).and_then(Value::as_u64).unwrap_or(0);
+            let max = map.get(max).and_then(Value::as_u64).unwrap_or(u64::MAX);
+            (min, max)
+        };
+        res = res.and(Compat::of_ranges(
+            length_range(old, "minLength", "maxLength"),
+            length_range(new, "minLength", "maxLength"),
+        ));
+        res = res.and(Compat::of_ranges(
+            length_range(old, "minItems", "maxItems"),
+            length_range(new, "minItems", "maxItems"),
+        ));
+
+        // Unsupported constraints must be equal, if both are set
+        for keyword in ["pattern", "not"] {
+            res = res.and(match (old.get(keyword), new.get(keyword)) {
+                (None, None) => Compat::Equal,
+                (Some(_), None) => Compat::Wider,
+                (None, Some(_)) => Compat::Narrower,
+                (Some(old), Some(new)) if old == new => Compat::Equal,
+                (Some(_), Some(_)) => Compat::Incompatible,
+            });
+        }
+        // Non-integer formats
+        let format = |map: &Map<String, Value>| {
+            map.get("format")
+                .and_then(Value::as_str)
+                .filter(|format| integer_format_range(format).is_none())
+                .map(str::to_owned)
+        };
+        res = res.and(match (format(old), format(new)) {
+            (None, None) => Compat::Equal,
+            (Some(old), Some(new)) if old == new => Compat::Equal,
+            (Some(_), None) => Compat::Wider,
+            (None, Some(_)) => Compat::Narrower,
+            (Some(_), Some(_)) => Compat::Incompatible,
+        });
+
+        // Array items
+        let true_schema = Value::Bool(true);
+        if old.contains_key("items") || new.contains_key("items") {
+            let old_items = old.get("items").unwrap_or(&true_schema);
+            let new_items = new.get("items").unwrap_or(&true_schema);
+            res = res.and(self.compare(old_items, new_items));
+        }
+        let prefix_items = |map: &Map<String, Value>| match map.get("prefixItems") {
+            Some(Value::Array(prefix_items)) => prefix_items.clone(),
+            _ => Vec::new(),
+        };
+        let (old_prefix_items, new_prefix_items) = (prefix_items(old), prefix_items(new));
+        if old_prefix_items.len() != new_prefix_items.len() {
+            return Compat::Incompatible;
+        }
+        for (old_item, new_item) in old_prefix_items.iter().zip(&new_prefix_items) {
+            res = res.and(self.compare(old_item, new_item));
+        }
+
+        // Object properties
+        let properties = |map: &Map<String, Value>| match map.get("properties") {
+            Some(Value::Object(properties)) => properties.clone(),
+            _ => empty.clone(),
+        };
+        let (old_properties, new_properties) = (properties(old), properties(new));
+        let names: BTreeSet<&String> = old_properties.keys().chain(new_properties.keys()).collect();
+        for name in names {
+            res = res.and(match (old_properties.get(name), new_properties.get(name)) {
+                (Some(old), Some(new)) => self.compare(old, new),
+                (Some(_), None) => Compat::Wider,
+                (None, Some(_)) => Compat::Narrower,
+                (None, None) => Compat::Equal,
+            });
+        }
+        let required = |map: &Map<String, Value>| -> Option<BTreeSet<String>> {
+            match map.get("required") {
+                Some(Value::Array(required)) => Some(
+                    required
+                        .iter()
+                        .filter_map(Value::as_str)
+                        .map(str::to_owned)
+                        .collect(),
+                ),
+                _ => None,
+            }
+        };
+        // More required properties accept fewer values
+        let (old_required, new_required) = (
+            required(old).unwrap_or_default(),
+            required(new).unwrap_or_default(),
+        );
+        res = res.and(Compat::of_sets(Some(&new_required), Some(&old_required)));
+        if old.contains_key("additionalProperties") || new.contains_key("additionalProperties") {
+            let old_additional = old.get("additionalProperties").unwrap_or(&true_schema);
+            let new_additional = new.get("additionalProperties").unwrap_or(&true_schema);
+            res = res.and(self.compare(old_additional, new_additional));
+        }
+
+        // Other composition keywords
+        for keyword in ["oneOf", "anyOf", "allOf"] {
+            res = res.and(match (old.get(keyword), new.get(keyword)) {
+                (None, None) => Compat::Equal,
+                (Some(_), None) => Compat::Wider,
+                (None, Some(_)) => Compat::Narrower,
+                (Some(old), Some(new)) => {
+                    let mut old = old.clone();
+                    let mut new = new.clone();
+                    // Compare as a union, even if it is not one
+                    if let (Value::Array(_), Value::Array(_)) = (&old, &new) {
+                        old = serde_json::json!({ "anyOf": old });
+                        new = serde_json::json!({ "anyOf": new });
+                    }
+                    self.compare(&old, &new)
+                }
+            });
+        }
+        res
+    }
+}
+
+/// Param names and schemas for a method
+fn params<'a>(doc: &'a Value, method: &str, names: &[&'a str]) -> Vec<(String, &'a Value)> {
+    param_schema_paths(doc, method, names)
+        .iter()
+        .zip(names)
+        .filter_map(|(schema_path, name)| Some(((*name).to_owned(), doc.pointer(schema_path)?)))
+        .collect()
+}
+
+/// Method names in a JSON document, including aliases, with their paths
+fn methods(doc: &Value) -> BTreeMap<String, String> {
+    let mut res = BTreeMap::new();
+    for (path, operation) in json_operations(doc) {
+        res.insert(path.to_owned(), path.to_owned());
+        for alias in json_extension_strs(operation, "x-aliases").unwrap_or_default() {
+            res.insert(alias.to_owned(), path.to_owned());
+        }
+    }
+    res
+}
+
+/// Classify the changes between two versions of a document.
+///
+/// Methods are compared by path. Params are compared by name, using the
+/// `x-param-names` extension. Documents without the extension are treated
+/// as having a single param, `params`, if they have a request body.
+pub fn diff(old: &OpenApi, new: &OpenApi) -> Diff {
+    let old_doc = serde_json::to_value(old).expect("document should serialize");
+    let new_doc = serde_json::to_value(new).expect("document should serialize");
+    diff_json(&old_doc, &new_doc)
+}
+
+/// Classify the changes between two versions of a document, as JSON.
+/// See [`diff`].
+pub fn diff_json(old_doc: &Value, new_doc: &Value) -> Diff {
+    let (old, new) = (old_doc, new_doc);
+    let mut comparer = Comparer {
+        old: Validator::new(old_doc),
+        new: Validator::new(new_doc),
+        visiting: HashSet::new(),
+    };
+    let mut changes = Vec::new();
+    let (old_methods, new_methods) = (methods(old), methods(new));
+    for method in old_methods.keys() {
+        if !new_methods.contains_key(method) {
+            changes.push(Change::MethodRemoved(method.clone()));
+        }
+    }
+    for method in new_methods.keys() {
+        if !old_methods.contains_key(method) {
+            changes.push(Change::MethodAdded(method.clone()));
+        }
+    }
+    let old_operations: BTreeMap<_, _> = json_operations(old).collect();
+    for (method, new_operation) in json_operations(new) {
+        let Some(old_operation) = old_operations.get(method) else {
+            continue;
+        };
+        let old_names = json_param_names(old_operation);
+        let new_names = json_param_names(new_operation);
+        let old_params = params(old_doc, method, &old_names);
+        let new_params = params(new_doc, method, &new_names);
+        for (old_idx, (name, old_schema)) in old_params.iter().enumerate() {
+            let Some(new_idx) = new_params.iter().position(|(new_name, _)| new_name == name) else {
+                changes.push(Change::ParamRemoved {
+                    method: method.to_owned(),
+                    param: name.clone(),
+                });
+                continue;
+            };
+            if new_idx != old_idx {
+                changes.push(Change::ParamMoved {
+                    method: method.to_owned(),
+                    param: name.clone(),
+                    old_idx,
+                    new_idx,
+                });
+            }
+            let compat = comparer.compare(old_schema, new_params[new_idx].1);
+            if compat != Compat::Equal {
+                changes.push(Change::ParamChanged {
+                    method: method.to_owned(),
+                    param: name.clone(),
+                    compat,
+                });
+            }
+        }
+        for (name, new_schema) in &new_params {
+            if !old_params.iter().any(|(old_name, _)| old_name == name) {
+                let required = !comparer.new.is_valid(new_schema, "", &Value::Null);
+                changes.push(Change::ParamAdded {
+                    method: method.to_owned(),
+                    param: name.clone(),
+                    required,
+                });
+            }
+        }
+        let result_schema = |doc: &Value| {
+            let schema_path = format!(
+                "/paths/{}/post/responses/200/content/application~1json/schema",
+                escape_pointer_segment(method)
+            );
+            doc.pointer(&schema_path)
+                .cloned()
+                .unwrap_or(Value::Bool(true))
+        };
+        let compat = comparer.compare(&result_schema(old_doc), &result_schema(new_doc));
+        if compat != Compat::Equal {
+            changes.push(Change::ResultChanged {
+                method: method.to_owned(),
+                compat,
+            });
+        }
+    }
+    Diff(changes)
+}
@@ -0,0 +1,238 @@
+//! Runtime builder for documenting methods that are not generated from an
+//! RPC trait, eg. methods registered with
+//! `RpcModule::register_async_method`
+
+use std::collections::BTreeMap;
+
+use utoipa::{
+    openapi::{
+        extensions::ExtensionsBuilder,
+        path::{HttpMethod, Operation},
+        request_body::RequestBody,
+        Components, ContentBuilder, Object, OpenApi, OpenApiBuilder, PathItem, PathsBuilder, RefOr,
+        ResponseBuilder, Schema,
+    },
+    PartialSchema, ToSchema,
+};
+
+/// Param types for a method.
+/// Implemented for tuples of types that implement [`PartialSchema`].
+pub trait Params {
+    /// Schema for each param
+    fn schemas() -> Vec<RefOr<Schema>>;
+}
+
+macro_rules! impl_params_for_tuple {
+    ($($param:ident),*) => {
+        impl<$($param),*> Params for ($($param,)*)
+        where
+            $($param: PartialSchema),*
+        {
+            fn schemas() -> Vec<RefOr<Schema>> {
+                vec![$(<$param as PartialSchema>::schema()),*]
+            }
+        }
+    };
+}
+
+impl_params_for_tuple!();
+impl_params_for_tuple!(P0);
+impl_params_for_tuple!(P0, P1);
+impl_params_for_tuple!(P0, P1, P2);
+impl_params_for_tuple!(P0, P1, P2, P3);
+impl_params_for_tuple!(P0, P1, P2, P3, P4);
+impl_params_for_tuple!(P0, P1, P2, P3, P4, P5);
+impl_params_for_tuple!(P0, P1, P2, P3, P4, P5, P6);
+impl_params_for_tuple!(P0, P1, P2, P3, P4, P5, P6, P7);
+impl_params_for_tuple!(P0, P1, P2, P3, P4, P5, P6, P7, P8);
+impl_params_for_tuple!(P0, P1, P2, P3, P4, P5, P6, P7, P8, P9);
+impl_params_for_tuple!(P0, P1, P2, P3, P4, P5, P6, P7, P8, P9, P10);
+impl_params_for_tuple!(P0, P1, P2, P3, P4, P5, P6, P7, P8, P9, P10, P11);
+impl_params_for_tuple!(P0, P1, P2, P3, P4, P5, P6, P7, P8, P9, P10, P11, P12);
+impl_params_for_tuple!(P0, P1, P2, P3, P4, P5, P6, P7, P8, P9, P10, P11, P12, P13);
+impl_params_for_tuple!(P0, P1, P2, P3, P4, P5, P6, P7, P8, P9, P10, P11, P12, P13, P14);
+impl_params_for_tuple!(P0, P1, P2, P3, P4, P5, P6, P7, P8, P9, P10, P11, P12, P13, P14, P15);
+
+/// Error when the number of param names is not the number of params
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParamNamesError {
+    /// Number of params
+    pub params: usize,
+    pub param_names: Vec<String>,
+}
+
+impl std::fmt::Display for ParamNamesError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "expected a name for each of {} params, found {:?}",
+            self.params, self.param_names
+        )
+    }
+}
+
+impl std::error::Error for ParamNamesError {}
+
+/// Documentation for a single method
+struct MethodDoc {
+    param_schemas: Vec<RefOr<Schema>>,
+    param_names: Option<Vec<String>>,
+    output_schema: RefOr<Schema>,
+    description: Option<String>,
+}
+
+impl MethodDoc {
+    /// Same shape as the operations generated by `#[open_api]`
+    fn operation(self, name: &str) -> Operation {
+        let Self {
+            mut param_schemas,
+            param_names,
+            output_schema,
+            description,
+        } = self;
+        let mut operation = Operation::new();
+        operation.description = description;
+        operation.operation_id = Some(name.to_owned());
+        operation.request_body = if param_schemas.is_empty() {
+            None
+        } else {
+            // Params are named by position, if names are not set
+            let param_names = param_names.unwrap_or_else(|| {
+                (0..param_schemas.len())
+                    .map(|idx| idx.to_string())
+                    .collect()
+            });
+            operation.extensions = Some(
+                ExtensionsBuilder::new()
+                    .add("x-param-names", param_names.clone())
+                    .build(),
+            );
+            let content_schema = if param_schemas.len() == 1 {
+                param_schemas.remove(0)
+            } else {
+                let mut schema = Object::new();
+                schema
+                    .properties
+                    .extend(param_names.into_iter().zip(param_schemas));
+                RefOr::T(Schema::Object(schema))
+            };
+            let mut request_body = RequestBody::new();
+            let content = ContentBuilder::new().schema(Some(content_schema)).build();
+            request_body
+                .content
+                .insert("application/json".to_owned(), content);
+            Some(request_body)
+        };
+        let response = {
+            let content = ContentBuilder::new().schema(Some(output_schema)).build();
+            ResponseBuilder::new()
+                .content("application/json".to_owned(), content)
+                .build()
+        };
+        operation
+            .responses
+            .responses
+            .insert("200".to_owned(), RefOr::T(response));
+        operation
+    }
+}
+
+/// Builder for a single method
+pub struct MethodBuilder<'a>(&'a mut MethodDoc);
+
+impl MethodBuilder<'_> {
+    /// Set the method description
+    pub fn description<S>(self, description: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.0.description = Some(description.into());
+        self
+    }
+
+    /// Set param names, used as property names if the method has more than
+    /// one param, and to validate params passed by name.
+    ///
+    /// # Errors
+    /// Returns an error if the number of names is not the number of params.
+    pub fn param_names<I, S>(self, param_names: I) -> Result<Self, ParamNamesError>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let param_names: Vec<String> = param_names.into_iter().map(Into::into).collect();
+        if param_names.len() != self.0.param_schemas.len() {
+            return Err(ParamNamesError {
+                params: self.0.param_schemas.len(),
+                param_names,
+            });
+        }
+        self.0.param_names = Some(param_names);
+        Ok(self)
+    }
+}
+
+/// Runtime builder for documents.
+///
+/// Documents can be combined with documents generated by `#[open_api]` via
+/// [`crate::merge_all`], eg.
+/// `merge_all([ADoc::openapi(), doc_builder.build()])`.
+#[derive(Default)]
+pub struct DocBuilder {
+    methods: BTreeMap<String, MethodDoc>,
+    components: Components,
+}
+
+impl DocBuilder {
+    /// Builder with no methods or schemas
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Document a method, with params `P` and result `R`.
+    /// If a method with the same name was already documented, it is replaced.
+    pub fn method<P, R>(&mut self, name: &str) -> MethodBuilder<'_>
+    where
+        P: Params,
+        R: PartialSchema,
+    {
+        let method_doc = MethodDoc {
+            param_schemas: P::schemas(),
+            param_names: None,
+            output_schema: R::schema(),
+            description: None,
+        };
+        self.methods.insert(name.to_owned(), method_doc);
+        MethodBuilder(self.methods.get_mut(name).unwrap())
+    }
+
+    /// Add a reference schema to components
+    pub fn schema<T>(&mut self) -> &mut Self
+    where
+        T: ToSchema,
+    {
+        self.components
+            .schemas
+            .insert(T::name().into_owned(), T::schema());
+        self
+    }
+
+    /// Build a document with the documented methods and schemas
+    pub fn build(self) -> OpenApi {
+        let Self {
+            methods,
+            components,
+        } = self;
+        let paths = methods
+            .into_iter()
+            .fold(PathsBuilder::new(), |paths, (name, method_doc)| {
+                let operation = method_doc.operation(&name);
+                paths.path(name, PathItem::new(HttpMethod::Post, operation))
+            })
+            .build();
+        OpenApiBuilder::new()
+            .paths(paths)
+            .components(Some(components))
+            .build()
+    }
+}
@@ -0,0 +1,131 @@
+//! Write spec files for documents
+
+use std::path::{Path, PathBuf};
+
+use crate::{
+    canonical_json,
+    merge::Docs,
+    openrpc::openrpc,
+    typescript::{typescript, TypeScriptError},
+};
+
+/// Error when exporting documents
+#[derive(Debug)]
+pub enum ExportError {
+    /// A document could not be serialized as YAML
+    #[cfg(feature = "yaml")]
+    Yaml {
+        path: PathBuf,
+        err: Box<dyn std::error::Error + Send + Sync>,
+    },
+    Io {
+        path: PathBuf,
+        err: std::io::Error,
+    },
+    /// TypeScript types could not be generated for a document
+    TypeScript {
+        path: PathBuf,
+        err: TypeScriptError,
+    },
+    /// Doc structs have the same name, without module paths or generic
+    /// arguments, so their files would overwrite each other
+    DuplicateName {
+        name: &'static str,
+    },
+}
+
+impl std::fmt::Display for ExportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            #[cfg(feature = "yaml")]
+            Self::Yaml { path, err } => {
+                write!(f, "failed to serialize `{}` as YAML: {err}", path.display())
+            }
+            Self::Io { path, err } => write!(f, "failed to write `{}`: {err}", path.display()),
+            Self::TypeScript { path, err } => {
+                write!(f, "failed to generate `{}`: {err}", path.display())
+            }
+            Self::DuplicateName { name } => write!(
+                f,
+                "multiple doc structs are named `{name}`; rename one with `doc_struct = \"..\"`"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ExportError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            #[cfg(feature = "yaml")]
+            Self::Yaml { err, .. } => Some(&**err),
+            Self::Io { err, .. } => Some(err),
+            Self::TypeScript { err, .. } => Some(err),
+            Self::DuplicateName { .. } => None,
+        }
+    }
+}
+
+/// Write spec files for each document in a tuple of doc structs, eg.
+/// `l2l_openapi::export::<(ADoc, BDoc)>("spec")`.
+///
+/// For a doc struct `NodeDoc`, the following files are written:
+/// * `NodeDoc.json`: the document as canonical JSON
+/// * `NodeDoc.yaml`: the document as YAML, if the `yaml` feature is enabled
+/// * `NodeDoc.openrpc.json`: the document converted to OpenRPC
+/// * `NodeDoc.ts`: TypeScript types for the document
+///
+/// The output directory is created if it does not exist.
+/// Returns the paths of the written files.
+/// Nothing is written if two doc structs have the same name, eg. generic
+/// doc structs with different type arguments.
+pub fn export<D>(out_dir: impl AsRef<Path>) -> Result<Vec<PathBuf>, ExportError>
+where
+    D: Docs,
+{
+    let names = D::names();
+    for (idx, name) in names.iter().enumerate() {
+        if names[..idx].contains(name) {
+            return Err(ExportError::DuplicateName { name });
+        }
+    }
+    let out_dir = out_dir.as_ref();
+    std::fs::create_dir_all(out_dir).map_err(|err| ExportError::Io {
+        path: out_dir.to_owned(),
+        err,
+    })?;
+    let mut paths = Vec::new();
+    let mut write = |path: PathBuf, contents: String| {
+        std::fs::write(&path, contents).map_err(|err| ExportError::Io {
+            path: path.clone(),
+            err,
+        })?;
+        paths.push(path);
+        Ok(())
+    };
+    for (name, openapi) in names.into_iter().zip(D::openapis()) {
+        write(
+            out_dir.join(format!("{name}.json")),
+            canonical_json(&openapi),
+        )?;
+        #[cfg(feature = "yaml")]
+        {
+            let path = out_dir.join(format!("{name}.yaml"));
+            let yaml = openapi.to_yaml().map_err(|err| ExportError::Yaml {
+                path: path.clone(),
+                err: Box::new(err),
+            })?;
+            write(path, yaml)?;
+        }
+        let mut openrpc_json =
+            serde_json::to_string_pretty(&openrpc(&openapi)).expect("JSON should serialize");
+        openrpc_json.push('\n');
+        write(out_dir.join(format!("{name}.openrpc.json")), openrpc_json)?;
+        let path = out_dir.join(format!("{name}.ts"));
+        let ts = typescript(&openapi).map_err(|err| ExportError::TypeScript {
+            path: path.clone(),
+            err,
+        })?;
+        write(path, ts)?;
+    }
+    Ok(paths)
+}
@@ -0,0 +1,29 @@
+//! Helpers for the tests generated by `#[open_api(validate_test)]` and
+//! `#[open_api(snapshot = "..")]`.
+//!
+//! Generated tests are not supported for traits declared in function bodies,
+//! since the tests could not be run:
+//! ```compile_fail,E0277
+//! use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+//!
+//! fn main() {
+//!     #[l2l_openapi::open_api(validate_test)]
+//!     #[rpc(server)]
+//!     pub trait Local {
+//!         #[method(name = "local")]
+//!         async fn local(&self) -> RpcResult<u32>;
+//!     }
+//! }
+//! ```
+
+/// Receiver for the method that returns a doc for the generated tests
+pub struct ModuleScope;
+
+/// Not implemented, so that the generated tests fail to compile if the doc
+/// struct is not in a module
+#[diagnostic::on_unimplemented(
+    message = "`validate_test` and `snapshot` cannot be used with traits declared in function bodies",
+    label = "generated tests cannot be run from a function body",
+    note = "declare the trait in a module"
+)]
+pub trait InModule {}
@@ -0,0 +1,32 @@
+//! `help` RPC method, as in bitcoind
+
+#[cfg(feature = "server")]
+use jsonrpsee::{
+    types::{error::INVALID_PARAMS_CODE, ErrorObject, ErrorObjectOwned},
+    RpcModule,
+};
+
+/// Name of the help method
+pub const HELP_METHOD: &str = "help";
+
+/// RPC module with a `help` method, that takes an optional method name.
+/// Usually constructed with `{Trait}Doc::help_module()`, which is generated
+/// by `#[open_api(help)]`.
+#[cfg(feature = "server")]
+pub fn help_module(help: fn(Option<&str>) -> Option<&'static str>) -> RpcModule<()> {
+    let mut module = RpcModule::new(());
+    module
+        .register_method(HELP_METHOD, move |params, _, _| {
+            let method: Option<String> = params.sequence().optional_next()?;
+            help(method.as_deref()).ok_or_else(|| -> ErrorObjectOwned {
+                let method = method.unwrap_or_default();
+                ErrorObject::owned(
+                    INVALID_PARAMS_CODE,
+                    format!("unknown method `{method}`"),
+                    None::<()>,
+                )
+            })
+        })
+        .expect("method name should not be registered");
+    module
+}
@@ -0,0 +1,440 @@
+//! Validate JSON values against the schemas in a document.
+//!
+//! Supports the subset of JSON Schema that is generated by utoipa.
+//! `pattern` and `format`s other than integer formats are not checked.
+
+use serde_json::{Map, Value};
+
+use crate::util::escape_pointer_segment;
+
+/// Error when validating a value against a schema
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SchemaError {
+    /// JSON pointer to the value that failed validation
+    pub instance_path: String,
+    /// JSON pointer into the document, to the schema keyword that failed
+    pub schema_path: String,
+    pub message: String,
+}
+
+impl SchemaError {
+    pub fn to_json(&self) -> Value {
+        serde_json::json!({
+            "instancePath": self.instance_path,
+            "schemaPath": self.schema_path,
+            "message": self.message,
+        })
+    }
+}
+
+impl std::fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let instance_path = if self.instance_path.is_empty() {
+            "/"
+        } else {
+            &self.instance_path
+        };
+        write!(
+            f,
+            "{instance_path}: {} (schema: {})",
+            self.message, self.schema_path
+        )
+    }
+}
+
+/// Range of integer formats
+pub(crate) fn integer_format_range(format: &str) -> Option<(i128, i128)> {
+    let range = match format {
+        "int8" => (i8::MIN as i128, i8::MAX as i128),
+        "int16" => (i16::MIN as i128, i16::MAX as i128),
+        "int32" => (i32::MIN as i128, i32::MAX as i128),
+        "int64" => (i64::MIN as i128, i64::MAX as i128),
+        "uint8" => (0, u8::MAX as i128),
+        "uint16" => (0, u16::MAX as i128),
+        "uint32" => (0, u32::MAX as i128),
+        "uint64" => (0, u64::MAX as i128),
+        _ => return None,
+    };
+    Some(range)
+}
+
+/// Returns the value as an integer, if it is an integer
+fn as_integer(value: &Value) -> Option<i128> {
+    match value {
+        Value::Number(number) => {
+            if let Some(n) = number.as_i64() {
+                Some(n as i128)
+            } else if let Some(n) = number.as_u64() {
+                Some(n as i128)
+            } else {
+                number
+                    .as_f64()
+                    .filter(|n| n.fract() == 0.0 && n.abs() < 2f64.powi(96))
+                    .map(|n| n as i128)
+            }
+        }
+        _ => None,
+    }
+}
+
+fn type_matches(ty: &str, instance: &Value) -> bool {
+    match ty {
+        "null" => instance.is_null(),
+        "boolean" => instance.is_boolean(),
+        "string" => instance.is_string(),
+        "array" => instance.is_array(),
+        "object" => instance.is_object(),
+        "number" => instance.is_number(),
+        "integer" => as_integer(instance).is_some(),
+        _ => true,
+    }
+}
+
+fn type_name(instance: &Value) -> &'static str {
+    match instance {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Validates values against schemas in a document
+#[derive(Clone, Copy)]
+pub(crate) struct Validator<'a> {
+    /// Document, used to resolve `$ref`s
+    doc: &'a Value,
+}
+
+impl<'a> Validator<'a> {
+    pub fn new(doc: &'a Value) -> Self {
+        Self { doc }
+    }
+
+    /// Resolve a local `$ref`
+    pub fn resolve(&self, reference: &str) -> Option<&'a Value> {
+        reference
+            .strip_prefix('#')
+            .and_then(|pointer| self.doc.pointer(pointer))
+    }
+
+    /// Validate an instance against the schema at `schema_path` in the
+    /// document
+    pub fn validate_at(
+        &self,
+        schema_path: &str,
+        instance: &Value,
+        instance_path: &str,
+        errs: &mut Vec<SchemaError>,
+    ) {
+        match self.doc.pointer(schema_path) {
+            Some(schema) => self.validate(schema, schema_path, instance, instance_path, errs),
+            None => errs.push(SchemaError {
+                instance_path: instance_path.to_owned(),
+                schema_path: schema_path.to_owned(),
+                message: "schema does not exist".to_owned(),
+            }),
+        }
+    }
+
+    /// Returns true if the instance is valid
+    pub fn is_valid(&self, schema: &Value, schema_path: &str, instance: &Value) -> bool {
+        let mut errs = Vec::new();
+        self.validate(schema, schema_path, instance, "", &mut errs);
+        errs.is_empty()
+    }
+
+    pub fn validate(
+        &self,
+        schema: &Value,
+        schema_path: &str,
+        instance: &Value,
+        instance_path: &str,
+        errs: &mut Vec<SchemaError>,
+    ) {
+        let schema = match schema {
+            Value::Bool(true) => return,
+            Value::Object(schema) => schema,
+            _ => {
+                errs.push(SchemaError {
+                    instance_path: instance_path.to_owned(),
+                    schema_path: schema_path.to_owned(),
+                    message: "no values are allowed".to_owned(),
+                });
+                return;
+            }
+        };
+        if let Some(Value::String(reference)) = schema.get("$ref") {
+            match self.resolve(reference) {
+                Some(target) => {
+                    self.validate(target, &reference[1..], instance, instance_path, errs)
+                }
+                None => errs.push(SchemaError {
+                    instance_path: instance_path.to_owned(),
+                    schema_path: format!("{schema_path}/$ref"),
+                    message: format!("unresolved reference `{reference}`"),
+                }),
+            }
+            return;
+        }
+        let mut keyword_errs = Vec::new();
+        let mut err = |keyword: &str, message: String| {
+            keyword_errs.push(SchemaError {
+                instance_path: instance_path.to_owned(),
+                schema_path: format!("{schema_path}/{keyword}"),
+                message,
+            })
+        };
+        match schema.get("type") {
+            Some(Value::String(ty)) if !type_matches(ty, instance) => {
+                err(
+                    "type",
+                    format!("expected {ty}, found {}", type_name(instance)),
+                );
+                errs.append(&mut keyword_errs);
+                return;
+            }
+            Some(Value::Array(tys))
+                if !tys
+                    .iter()
+                    .any(|ty| ty.as_str().is_some_and(|ty| type_matches(ty, instance))) =>
+            {
+                let tys = Value::Array(tys.clone());
+                err(
+                    "type",
+                    format!("expected one of {tys}, found {}", type_name(instance)),
+                );
+                errs.append(&mut keyword_errs);
+                return;
+            }
+            _ => (),
+        }
+        if let Some(Value::Array(variants)) = schema.get("enum") {
+            if !variants.contains(instance) {
+                err(
+                    "enum",
+                    format!(
+                        "{instance} is not one of {}",
+                        Value::Array(variants.clone())
+                    ),
+                );
+            }
+        }
+        if let Some(value) = schema.get("const") {
+            if value != instance {
+                err("const", format!("expected {value}"));
+            }
+        }
+        match instance {
+            Value::Number(number) => {
+                let n = number.as_f64().unwrap_or(f64::NAN);
+                if let Some(minimum) = schema.get("minimum").and_then(Value::as_f64) {
+                    if n < minimum {
+                        err("minimum", format!("{number} is less than {minimum}"));
+                    }
+                }
+                if let Some(maximum) = schema.get("maximum").and_then(Value::as_f64) {
+                    if n > maximum {
+                        err("maximum", format!("{number} is greater than {maximum}"));
+                    }
+                }
+                if let Some(minimum) = schema.get("exclusiveMinimum").and_then(Value::as_f64) {
+                    if n <= minimum {
+                        err(
+                            "exclusiveMinimum",
+                            format!("{number} is not greater than {minimum}"),
+                        );
+                    }
+                }
+                if let Some(maximum) = schema.get("exclusiveMaximum").and_then(Value::as_f64) {
+                    if n >= maximum {
+                        err(
+                            "exclusiveMaximum",
+                            format!("{number} is not less than {maximum}"),
+                        );
+                    }
+                }
+                let format_range = schema
+                    .get("format")
+                    .and_then(Value::as_str)
+                    .and_then(integer_format_range);
+                if let Some((min, max)) = format_range {
+                    match as_integer(instance) {
+                        Some(n) if n < min || n > max => {
+                            err("format", format!("{number} is out of range"))
+                        }
+                        Some(_) => (),
+                        None => err("format", format!("{number} is not an integer")),
+                    }
+                }
+            }
+            Value::String(string) => {
+                let len = string.chars().count() as u64;
+                if let Some(min_length) = schema.get("minLength").and_then(Value::as_u64) {
+                    if len < min_length {
+                        err("minLength", format!("shorter than {min_length} characters"));
+                    }
+                }
+                if let Some(max_length) = schema.get("maxLength").and_then(Value::as_u64) {
+                    if len > max_length {
+                        err("maxLength", format!("longer than {max_length} characters"));
+                    }
+                }
+            }
+            Value::Array(items) => {
+                let len = items.len() as u64;
+                if let Some(min_items) = schema.get("minItems").and_then(Value::as_u64) {
+                    if len < min_items {
+                        err("minItems", format!("fewer than {min_items} items"));
+                    }
+                }
+                if let Some(max_items) = schema.get("maxItems").and_then(Value::as_u64) {
+                    if len > max_items {
+                        err("maxItems", format!("more than {max_items} items"));
+                    }
+                }
+                let mut prefix_len = 0;
+                if let Some(Value::Array(prefix_items)) = schema.get("prefixItems") {
+                    prefix_len = prefix_items.len();
+                    for (idx, (item_schema, item)) in prefix_items.iter().zip(items).enumerate() {
+                        self.validate(
+                            item_schema,
+                            &format!("{schema_path}/prefixItems/{idx}"),
+                            item,
+                            &format!("{instance_path}/{idx}"),
+                            errs,
+                        );
+                    }
+                }
+                if let Some(items_schema) = schema.get("items") {
+                    for (idx, item) in items.iter().enumerate().skip(prefix_len) {
+                        self.validate(
+                            items_schema,
+                            &format!("{schema_path}/items"),
+                            item,
+                            &format!("{instance_path}/{idx}"),
+                            errs,
+                        );
+                    }
+                }
+            }
+            Value::Object(object) => {
+                self.validate_object(schema, schema_path, object, instance_path, errs)
+            }
+            Value::Null | Value::Bool(_) => (),
+        }
+        errs.append(&mut keyword_errs);
+        if let Some(Value::Array(subschemas)) = schema.get("allOf") {
+            for (idx, subschema) in subschemas.iter().enumerate() {
+                let subschema_path = format!("{schema_path}/allOf/{idx}");
+                self.validate(subschema, &subschema_path, instance, instance_path, errs);
+            }
+        }
+        if let Some(Value::Array(subschemas)) = schema.get("anyOf") {
+            let any_valid = subschemas.iter().enumerate().any(|(idx, subschema)| {
+                self.is_valid(subschema, &format!("{schema_path}/anyOf/{idx}"), instance)
+            });
+            if !any_valid {
+                errs.push(SchemaError {
+                    instance_path: instance_path.to_owned(),
+                    schema_path: format!("{schema_path}/anyOf"),
+                    message: "does not match any schema".to_owned(),
+                });
+            }
+        }
+        if let Some(Value::Array(subschemas)) = schema.get("oneOf") {
+            let n_valid = subschemas
+                .iter()
+                .enumerate()
+                .filter(|(idx, subschema)| {
+                    self.is_valid(subschema, &format!("{schema_path}/oneOf/{idx}"), instance)
+                })
+                .count();
+            if n_valid != 1 {
+                errs.push(SchemaError {
+                    instance_path: instance_path.to_owned(),
+                    schema_path: format!("{schema_path}/oneOf"),
+                    message: format!("matches {n_valid} schemas, expected exactly 1"),
+                });
+            }
+        }
+        if let Some(subschema) = schema.get("not") {
+            if self.is_valid(subschema, &format!("{schema_path}/not"), instance) {
+                errs.push(SchemaError {
+                    instance_path: instance_path.to_owned(),
+                    schema_path: format!("{schema_path}/not"),
+                    message: "matches a disallowed schema".to_owned(),
+                });
+            }
+        }
+    }
+
+    fn validate_object(
+        &self,
+        schema: &Map<String, Value>,
+        schema_path: &str,
+        object: &Map<String, Value>,
+        instance_path: &str,
+        errs: &mut Vec<SchemaError>,
+    ) {
+        if let Some(Value::Array(required)) = schema.get("required") {
+            for name in required.iter().filter_map(Value::as_str) {
+                if !object.contains_key(name) {
+                    errs.push(SchemaError {
+                        instance_path: instance_path.to_owned(),
+                        schema_path: format!("{schema_path}/required"),
+                        message: format!("missing required property `{name}`"),
+                    });
+                }
+            }
+        }
+        let properties = match schema.get("properties") {
+            Some(Value::Object(properties)) => Some(properties),
+            _ => None,
+        };
+        for (name, value) in object {
+            let escaped_name = escape_pointer_segment(name);
+            let instance_path = format!("{instance_path}/{escaped_name}");
+            if let Some(property_schema) = properties.and_then(|properties| properties.get(name)) {
+                let property_schema_path = format!("{schema_path}/properties/{escaped_name}");
+                self.validate(
+                    property_schema,
+                    &property_schema_path,
+                    value,
+                    &instance_path,
+                    errs,
+                );
+            } else if let Some(additional) = schema.get("additionalProperties") {
+                let additional_path = format!("{schema_path}/additionalProperties");
+                if additional == &Value::Bool(false) {
+                    errs.push(SchemaError {
+                        instance_path,
+                        schema_path: additional_path,
+                        message: format!("unexpected property `{name}`"),
+                    });
+                } else {
+                    self.validate(additional, &additional_path, value, &instance_path, errs);
+                }
+            }
+        }
+    }
+}
+
+/// Validate a value against the schema at `schema_path` in a document, eg.
+/// a param schema in an OpenRPC document. `$ref`s are resolved against the
+/// document.
+pub fn validate_value(
+    doc: &Value,
+    schema_path: &str,
+    value: &Value,
+) -> Result<(), Vec<SchemaError>> {
+    let mut errs = Vec::new();
+    Validator::new(doc).validate_at(schema_path, value, "", &mut errs);
+    if errs.is_empty() {
+        Ok(())
+    } else {
+        Err(errs)
+    }
+}
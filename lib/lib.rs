@@ -1,9 +1,80 @@
 //! Macros for deriving OpenAPI schemas from jsonrpsee RPC APIs.
+//!
+//! Doc generation is controlled by the `gen-doc` feature (enabled by default).
+//! If it is disabled, `#[open_api]` only strips its helper attributes.
+//!
+//! The `yaml` feature (enabled by default) adds YAML output to [`export`].
+//!
+//! Runtime helpers are controlled by features (all enabled by default):
+//! * `server`: RPC modules, eg. `discover_module`, and `check_coverage`.
+//!   Enabled by `gen-doc`.
+//! * `middleware`: RPC middleware, eg. `ValidateParams` and `ResponseCache`
+//! * `client`: the `testing` module, and `ReplicaRouter` (with `middleware`)
 
-pub use l2l_openapi_macros::open_api;
+mod audience;
+#[cfg(feature = "server")]
+mod coverage;
+mod diff;
+mod doc_builder;
+mod export;
+#[doc(hidden)]
+pub mod generated_tests;
+mod help;
+mod json_schema;
+mod merge;
+mod metadata;
+#[cfg(feature = "middleware")]
+mod middleware;
+mod openrpc;
+#[cfg(feature = "middleware")]
+mod read_only;
+#[doc(hidden)]
+pub mod request;
+mod snapshot;
+#[cfg(feature = "client")]
+pub mod testing;
+mod typescript;
+mod util;
+mod validate;
+mod version;
+
+pub use audience::{filter_audiences, DEFAULT_AUDIENCE};
+#[cfg(feature = "middleware")]
+pub use audience::{AudienceFilter, AudienceFilterLayer};
+#[cfg(feature = "server")]
+pub use coverage::{check_coverage, CoverageError};
+pub use diff::{diff, diff_json, Change, Compat, Diff};
+pub use doc_builder::{DocBuilder, MethodBuilder, ParamNamesError, Params};
+pub use export::{export, ExportError};
+#[cfg(feature = "server")]
+pub use help::help_module;
+pub use help::HELP_METHOD;
+pub use json_schema::{validate_value, SchemaError};
+pub use l2l_openapi_macros::{import, open_api};
+pub use merge::{merge, merge_all, Docs, MergeConflict, MergeError};
+pub use metadata::{MethodFlags, MethodInfo, ParamInfo};
+#[cfg(feature = "middleware")]
+pub use middleware::{ValidateParams, ValidateParamsLayer};
+#[cfg(feature = "server")]
+pub use openrpc::discover_module;
+pub use openrpc::{openrpc, openrpc_json, DISCOVER_METHOD, OPENRPC_VERSION};
+#[cfg(all(feature = "client", feature = "middleware"))]
+pub use read_only::{ReplicaRouter, ReplicaRouterLayer};
+#[cfg(feature = "middleware")]
+pub use read_only::{ResponseCache, ResponseCacheLayer};
+pub use snapshot::{canonical_json, check_snapshot, SnapshotError, BLESS_ENV_VAR};
+pub use typescript::{typescript, TypeScriptError};
+pub use validate::{validate, ValidationError, ValidationIssue};
+pub use version::{filter_version, VersionError};
 
 #[doc(hidden)]
 pub use jsonrpsee as __jsonrpsee;
 
+#[doc(hidden)]
+pub use serde as __serde;
+
+#[doc(hidden)]
+pub use serde_json as __serde_json;
+
 #[doc(hidden)]
 pub use utoipa as __utoipa;
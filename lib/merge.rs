@@ -0,0 +1,188 @@
+//! Compose documents generated from multiple RPC traits
+
+use std::collections::{BTreeMap, HashSet};
+
+use utoipa::openapi::OpenApi;
+
+use crate::util::operations;
+
+/// Conflict between documents that are being merged
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum MergeConflict {
+    /// More than one operation uses the same operation ID
+    DuplicateOperationId(String),
+    /// Component schemas with the same name have different definitions
+    ConflictingSchema(String),
+    /// Security schemes with the same name have different definitions
+    ConflictingSecurityScheme(String),
+    /// Document extensions with the same name have different values
+    ConflictingExtension(String),
+}
+
+impl std::fmt::Display for MergeConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::DuplicateOperationId(operation_id) => {
+                write!(f, "duplicate operation ID: `{operation_id}`")
+            }
+            Self::ConflictingSchema(name) => {
+                write!(f, "conflicting definitions for component schema `{name}`")
+            }
+            Self::ConflictingSecurityScheme(name) => {
+                write!(f, "conflicting definitions for security scheme `{name}`")
+            }
+            Self::ConflictingExtension(name) => {
+                write!(f, "conflicting values for document extension `{name}`")
+            }
+        }
+    }
+}
+
+/// Error when merging documents. Contains all conflicts that were found.
+#[derive(Clone, Debug)]
+pub struct MergeError(pub Vec<MergeConflict>);
+
+impl std::fmt::Display for MergeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "failed to merge documents")?;
+        self.0
+            .iter()
+            .try_for_each(|conflict| write!(f, "\n  {conflict}"))
+    }
+}
+
+impl std::error::Error for MergeError {}
+
+/// A list of documents.
+/// Implemented for tuples of types that implement [`utoipa::OpenApi`].
+pub trait Docs {
+    fn openapis() -> Vec<OpenApi>;
+
+    /// Names of the doc structs, without module paths or generic arguments
+    fn names() -> Vec<&'static str>;
+}
+
+/// Name of a type, without module path or generic arguments
+fn short_type_name<T>() -> &'static str {
+    let name = std::any::type_name::<T>();
+    let name = name.split_once('<').map_or(name, |(name, _)| name);
+    name.rsplit_once("::").map_or(name, |(_, name)| name)
+}
+
+macro_rules! impl_docs_for_tuple {
+    ($($doc:ident),+) => {
+        impl<$($doc),+> Docs for ($($doc,)+)
+        where
+            $($doc: utoipa::OpenApi),+
+        {
+            fn openapis() -> Vec<OpenApi> {
+                vec![$(<$doc as utoipa::OpenApi>::openapi()),+]
+            }
+
+            fn names() -> Vec<&'static str> {
+                vec![$(short_type_name::<$doc>()),+]
+            }
+        }
+    };
+}
+
+impl_docs_for_tuple!(D0);
+impl_docs_for_tuple!(D0, D1);
+impl_docs_for_tuple!(D0, D1, D2);
+impl_docs_for_tuple!(D0, D1, D2, D3);
+impl_docs_for_tuple!(D0, D1, D2, D3, D4);
+impl_docs_for_tuple!(D0, D1, D2, D3, D4, D5);
+impl_docs_for_tuple!(D0, D1, D2, D3, D4, D5, D6);
+impl_docs_for_tuple!(D0, D1, D2, D3, D4, D5, D6, D7);
+impl_docs_for_tuple!(D0, D1, D2, D3, D4, D5, D6, D7, D8);
+impl_docs_for_tuple!(D0, D1, D2, D3, D4, D5, D6, D7, D8, D9);
+impl_docs_for_tuple!(D0, D1, D2, D3, D4, D5, D6, D7, D8, D9, D10);
+impl_docs_for_tuple!(D0, D1, D2, D3, D4, D5, D6, D7, D8, D9, D10, D11);
+impl_docs_for_tuple!(D0, D1, D2, D3, D4, D5, D6, D7, D8, D9, D10, D11, D12);
+impl_docs_for_tuple!(D0, D1, D2, D3, D4, D5, D6, D7, D8, D9, D10, D11, D12, D13);
+impl_docs_for_tuple!(D0, D1, D2, D3, D4, D5, D6, D7, D8, D9, D10, D11, D12, D13, D14);
+impl_docs_for_tuple!(D0, D1, D2, D3, D4, D5, D6, D7, D8, D9, D10, D11, D12, D13, D14, D15);
+
+/// Merge documents, taking the union of paths, components and document
+/// extensions.
+/// The `info` of the first document is retained.
+pub fn merge_all<I>(openapis: I) -> Result<OpenApi, MergeError>
+where
+    I: IntoIterator<Item = OpenApi>,
+{
+    let mut conflicts = Vec::new();
+    let mut operation_ids = HashSet::new();
+    let mut schemas = BTreeMap::new();
+    let mut security_schemes = BTreeMap::new();
+    let mut extensions = BTreeMap::new();
+    let mut res: Option<OpenApi> = None;
+    for openapi in openapis {
+        for (_, operation) in operations(&openapi) {
+            if let Some(operation_id) = &operation.operation_id {
+                if !operation_ids.insert(operation_id.clone()) {
+                    conflicts.push(MergeConflict::DuplicateOperationId(operation_id.clone()));
+                }
+            }
+        }
+        if let Some(components) = &openapi.components {
+            for (name, schema) in &components.schemas {
+                match schemas.get(name) {
+                    Some(existing) if existing != schema => {
+                        conflicts.push(MergeConflict::ConflictingSchema(name.clone()))
+                    }
+                    Some(_) => (),
+                    None => {
+                        schemas.insert(name.clone(), schema.clone());
+                    }
+                }
+            }
+            for (name, scheme) in &components.security_schemes {
+                match security_schemes.get(name) {
+                    Some(existing) if existing != scheme => {
+                        conflicts.push(MergeConflict::ConflictingSecurityScheme(name.clone()))
+                    }
+                    Some(_) => (),
+                    None => {
+                        security_schemes.insert(name.clone(), scheme.clone());
+                    }
+                }
+            }
+        }
+        // `OpenApi::merge` ignores document extensions
+        let mut doc_extensions: Vec<_> = openapi.extensions.iter().flat_map(|e| e.iter()).collect();
+        doc_extensions.sort_by_key(|(name, _)| *name);
+        for (name, value) in doc_extensions {
+            match extensions.get(name) {
+                Some(existing) if existing != value => {
+                    conflicts.push(MergeConflict::ConflictingExtension(name.clone()))
+                }
+                Some(_) => (),
+                None => {
+                    extensions.insert(name.clone(), value.clone());
+                }
+            }
+        }
+        match &mut res {
+            Some(res) => res.merge(openapi),
+            None => res = Some(openapi),
+        }
+    }
+    if conflicts.is_empty() {
+        let mut res = res.unwrap_or_default();
+        if !extensions.is_empty() {
+            res.extensions = Some(extensions.into_iter().collect());
+        }
+        Ok(res)
+    } else {
+        Err(MergeError(conflicts))
+    }
+}
+
+/// Merge the documents for a tuple of doc structs, eg.
+/// `l2l_openapi::merge::<(ADoc, BDoc)>()`
+pub fn merge<D>() -> Result<OpenApi, MergeError>
+where
+    D: Docs,
+{
+    merge_all(D::openapis())
+}
@@ -0,0 +1,57 @@
+//! Runtime metadata for methods, generated as `{Trait}Doc::METHODS`
+
+/// Metadata for a method param
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ParamInfo {
+    /// Wire name of the param
+    pub name: &'static str,
+    /// Rust type of the param
+    pub ty: &'static str,
+}
+
+/// Flags for a method
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct MethodFlags {
+    /// The method is registered as blocking (`#[method(blocking)]`)
+    pub blocking: bool,
+    /// Params are passed by name (`#[method(param_kind = map)]`)
+    pub named_params: bool,
+    /// The method does not modify state (`#[open_api_method(read_only)]`)
+    pub read_only: bool,
+    /// Repeated calls with the same params have the same effect as one call
+    /// (`#[open_api_method(idempotent)]`)
+    pub idempotent: bool,
+    /// The method modifies state (`#[open_api_method(mutating)]`)
+    pub mutating: bool,
+}
+
+/// Metadata for a method
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct MethodInfo {
+    /// Wire name of the method, including the namespace
+    pub name: &'static str,
+    pub aliases: &'static [&'static str],
+    /// Params, in positional order
+    pub params: &'static [ParamInfo],
+    /// Rust type of the result, if the method has an output
+    pub result_ty: Option<&'static str>,
+    pub description: Option<&'static str>,
+    pub tags: &'static [&'static str],
+    pub deprecated: bool,
+    /// Audience of the method (`#[open_api_method(audience = "..")]`), or
+    /// [`crate::DEFAULT_AUDIENCE`]
+    pub audience: &'static str,
+    pub flags: MethodFlags,
+}
+
+impl MethodInfo {
+    /// Returns true if the method name or one of its aliases is `name`
+    pub fn has_name(&self, name: &str) -> bool {
+        self.name == name || self.aliases.contains(&name)
+    }
+
+    /// Find a method by name or alias
+    pub fn find<'a>(methods: &'a [Self], name: &str) -> Option<&'a Self> {
+        methods.iter().find(|method| method.has_name(name))
+    }
+}
@@ -0,0 +1,190 @@
+//! RPC middleware that validates params against the schemas in a document
+
+use std::{collections::HashMap, future::Future, sync::Arc};
+
+use jsonrpsee::{
+    core::middleware::{Batch, BatchEntry, BatchEntryErr, Notification, RpcServiceT},
+    types::{
+        error::{INVALID_PARAMS_CODE, INVALID_PARAMS_MSG},
+        ErrorObject, ErrorObjectOwned, Request,
+    },
+    MethodResponse,
+};
+use serde_json::Value;
+use utoipa::openapi::OpenApi;
+
+use crate::{
+    json_schema::{SchemaError, Validator},
+    util::{escape_pointer_segment, extension_strs, operations, param_schema_paths},
+};
+
+/// Documented params for a method
+#[derive(Debug)]
+struct MethodParams {
+    /// Param names, in order
+    names: Vec<String>,
+    /// JSON pointers to the schema for each param
+    schema_paths: Vec<String>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    /// Document as JSON
+    doc: Value,
+    /// Params for each method name, including aliases
+    methods: HashMap<String, Arc<MethodParams>>,
+}
+
+impl Inner {
+    fn new(openapi: &OpenApi) -> Self {
+        let doc = serde_json::to_value(openapi).expect("document should serialize");
+        let mut methods = HashMap::new();
+        for (path, operation) in operations(openapi) {
+            if operation.request_body.is_none() {
+                continue;
+            }
+            let Some(names) = extension_strs(operation, "x-param-names") else {
+                continue;
+            };
+            let method_params = Arc::new(MethodParams {
+                schema_paths: param_schema_paths(&doc, path, &names),
+                names: names.into_iter().map(str::to_owned).collect(),
+            });
+            let aliases = extension_strs(operation, "x-aliases").unwrap_or_default();
+            for name in std::iter::once(path).chain(aliases) {
+                methods.insert(name.to_owned(), method_params.clone());
+            }
+        }
+        Self { doc, methods }
+    }
+
+    /// Validate the params for a request
+    fn check(&self, request: &Request<'_>) -> Result<(), ErrorObjectOwned> {
+        let Some(method_params) = self.methods.get(request.method_name()) else {
+            return Ok(());
+        };
+        let params: Value = match &request.params {
+            None => Value::Array(Vec::new()),
+            // Malformed params are rejected by the handler
+            Some(params) => match serde_json::from_str(params.get()) {
+                Ok(params) => params,
+                Err(_) => return Ok(()),
+            },
+        };
+        let validator = Validator::new(&self.doc);
+        let mut errs = Vec::new();
+        match params {
+            Value::Array(params) => {
+                for idx in 0..method_params.names.len() {
+                    let param = params.get(idx).unwrap_or(&Value::Null);
+                    validator.validate_at(
+                        &method_params.schema_paths[idx],
+                        param,
+                        &format!("/{idx}"),
+                        &mut errs,
+                    );
+                }
+            }
+            Value::Object(params) => {
+                for (idx, name) in method_params.names.iter().enumerate() {
+                    let param = params.get(name).unwrap_or(&Value::Null);
+                    validator.validate_at(
+                        &method_params.schema_paths[idx],
+                        param,
+                        &format!("/{}", escape_pointer_segment(name)),
+                        &mut errs,
+                    );
+                }
+            }
+            // Rejected by the handler
+            _ => (),
+        }
+        if errs.is_empty() {
+            Ok(())
+        } else {
+            let data = Vec::from_iter(errs.iter().map(SchemaError::to_json));
+            Err(ErrorObject::owned(
+                INVALID_PARAMS_CODE,
+                INVALID_PARAMS_MSG,
+                Some(data),
+            ))
+        }
+    }
+}
+
+/// Layer for [`ValidateParams`]
+#[derive(Clone, Debug)]
+pub struct ValidateParamsLayer(Arc<Inner>);
+
+impl ValidateParamsLayer {
+    /// Validate params for the methods in a document.
+    /// Params are validated against the request body schema of each method.
+    pub fn new(openapi: &OpenApi) -> Self {
+        Self(Arc::new(Inner::new(openapi)))
+    }
+}
+
+impl<S> tower::Layer<S> for ValidateParamsLayer {
+    type Service = ValidateParams<S>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        ValidateParams {
+            inner: self.0.clone(),
+            service,
+        }
+    }
+}
+
+/// RPC middleware that validates params against the schemas in a document.
+/// Invalid calls are rejected with an `Invalid params` error, with a list of
+/// schema errors as data.
+/// Notifications, and methods that are not documented, are not validated.
+#[derive(Clone, Debug)]
+pub struct ValidateParams<S> {
+    inner: Arc<Inner>,
+    service: S,
+}
+
+impl<S> RpcServiceT for ValidateParams<S>
+where
+    S: RpcServiceT<MethodResponse = MethodResponse> + Send + Sync + Clone + 'static,
+{
+    type MethodResponse = S::MethodResponse;
+    type NotificationResponse = S::NotificationResponse;
+    type BatchResponse = S::BatchResponse;
+
+    fn call<'a>(
+        &self,
+        request: Request<'a>,
+    ) -> impl Future<Output = Self::MethodResponse> + Send + 'a {
+        let res = self.inner.check(&request);
+        let service = self.service.clone();
+        async move {
+            match res {
+                Ok(()) => service.call(request).await,
+                Err(err) => MethodResponse::error(request.id(), err),
+            }
+        }
+    }
+
+    fn batch<'a>(
+        &self,
+        mut requests: Batch<'a>,
+    ) -> impl Future<Output = Self::BatchResponse> + Send + 'a {
+        for entry in requests.iter_mut() {
+            if let Ok(BatchEntry::Call(request)) = entry {
+                if let Err(err) = self.inner.check(request) {
+                    *entry = Err(BatchEntryErr::new(request.id(), err));
+                }
+            }
+        }
+        self.service.batch(requests)
+    }
+
+    fn notification<'a>(
+        &self,
+        n: Notification<'a>,
+    ) -> impl Future<Output = Self::NotificationResponse> + Send + 'a {
+        self.service.notification(n)
+    }
+}
@@ -0,0 +1,164 @@
+//! Convert documents to OpenRPC
+
+#[cfg(feature = "server")]
+use jsonrpsee::RpcModule;
+use serde_json::{json, Map, Value};
+use utoipa::openapi::OpenApi;
+
+use crate::{
+    json_schema::Validator,
+    util::{json_operations, json_param_names, param_schema_paths},
+};
+
+/// Version of the OpenRPC specification that documents are converted to
+pub const OPENRPC_VERSION: &str = "1.3.2";
+
+/// Name of the OpenRPC service discovery method
+pub const DISCOVER_METHOD: &str = "rpc.discover";
+
+/// Example values for a method, keyed by example name
+fn content_examples(content: Option<&Value>) -> Option<&Map<String, Value>> {
+    content?
+        .get("application/json")?
+        .get("examples")?
+        .as_object()
+}
+
+/// OpenRPC example pairing, from a request body example and a result example
+fn example_pairing(
+    name: &str,
+    param_names: &[&str],
+    request_body: Option<&Value>,
+    result: Option<&Value>,
+) -> Value {
+    let params: Vec<Value> = match (param_names, request_body) {
+        (_, None) => Vec::new(),
+        ([param_name], Some(value)) => vec![json!({ "name": param_name, "value": value })],
+        (param_names, Some(value)) => param_names
+            .iter()
+            .filter_map(|param_name| {
+                let value = value.get(param_name)?;
+                Some(json!({ "name": param_name, "value": value }))
+            })
+            .collect(),
+    };
+    let mut pairing = json!({ "name": name, "params": params });
+    if let Some(result) = result {
+        pairing["result"] = json!({ "name": "result", "value": result });
+    }
+    pairing
+}
+
+/// OpenRPC method object for an operation
+fn method<'a>(doc: &Value, path: &str, operation: &'a Value) -> Value {
+    let validator = Validator::new(doc);
+    let mut method = Map::new();
+    method.insert("name".to_owned(), Value::String(path.to_owned()));
+    for key in ["summary", "description", "deprecated"] {
+        if let Some(value) = operation.get(key) {
+            method.insert(key.to_owned(), value.clone());
+        }
+    }
+    if let Some(Value::Array(tags)) = operation.get("tags") {
+        let tags = tags.iter().map(|tag| json!({ "name": tag })).collect();
+        method.insert("tags".to_owned(), Value::Array(tags));
+    }
+    let param_names = json_param_names(operation);
+    let params: Vec<Value> = param_schema_paths(doc, path, &param_names)
+        .into_iter()
+        .zip(&param_names)
+        .map(|(schema_path, name)| {
+            let schema = doc
+                .pointer(&schema_path)
+                .cloned()
+                .unwrap_or(Value::Bool(true));
+            // Params that accept null may be omitted
+            let required = !validator.is_valid(&schema, &schema_path, &Value::Null);
+            json!({ "name": name, "required": required, "schema": schema })
+        })
+        .collect();
+    method.insert("params".to_owned(), Value::Array(params));
+    if operation.get("x-param-structure") == Some(&json!("by-name")) {
+        method.insert("paramStructure".to_owned(), json!("by-name"));
+    }
+    let response = operation.pointer("/responses/200");
+    if let Some(schema) =
+        response.and_then(|response| response.pointer("/content/application~1json/schema"))
+    {
+        method.insert(
+            "result".to_owned(),
+            json!({ "name": "result", "schema": schema }),
+        );
+    }
+    let request_examples = content_examples(operation.pointer("/requestBody/content"));
+    let result_examples = content_examples(response.and_then(|response| response.get("content")));
+    let mut example_names: Vec<&String> = request_examples
+        .into_iter()
+        .chain(result_examples)
+        .flat_map(Map::keys)
+        .collect();
+    example_names.sort();
+    example_names.dedup();
+    if !example_names.is_empty() {
+        let examples = example_names
+            .into_iter()
+            .map(|name| {
+                let example_value =
+                    |examples: Option<&'a Map<String, Value>>| examples?.get(name)?.get("value");
+                example_pairing(
+                    name,
+                    &param_names,
+                    example_value(request_examples),
+                    example_value(result_examples),
+                )
+            })
+            .collect();
+        method.insert("examples".to_owned(), Value::Array(examples));
+    }
+    if let Value::Object(operation) = operation {
+        for (key, value) in operation {
+            if key.starts_with("x-") {
+                method.insert(key.clone(), value.clone());
+            }
+        }
+    }
+    Value::Object(method)
+}
+
+/// Convert a document to an OpenRPC document.
+/// Component schemas are retained, so `$ref`s are unchanged.
+/// Methods with `x-param-structure: "by-name"` have `paramStructure: "by-name"`.
+pub fn openrpc(openapi: &OpenApi) -> Value {
+    let doc = serde_json::to_value(openapi).expect("document should serialize");
+    openrpc_json(&doc)
+}
+
+/// Convert a document to an OpenRPC document, working on JSON values.
+/// Useful for documents that cannot be deserialized as `utoipa` types.
+pub fn openrpc_json(doc: &Value) -> Value {
+    let methods: Vec<Value> = json_operations(doc)
+        .map(|(path, operation)| method(doc, path, operation))
+        .collect();
+    let mut res = json!({
+        "openrpc": OPENRPC_VERSION,
+        "info": doc["info"],
+        "methods": methods,
+    });
+    if let Some(Value::Object(schemas)) = doc.pointer("/components/schemas") {
+        res["components"] = json!({ "schemas": schemas });
+    }
+    res
+}
+
+/// RPC module with an `rpc.discover` method, that returns the OpenRPC
+/// document for a document. Merge it into the server's module, eg.
+/// `module.merge(l2l_openapi::discover_module(&NodeDoc::openapi()))`.
+#[cfg(feature = "server")]
+pub fn discover_module(openapi: &OpenApi) -> RpcModule<()> {
+    let doc = openrpc(openapi);
+    let mut module = RpcModule::new(());
+    module
+        .register_method(DISCOVER_METHOD, move |_, _, _| doc.clone())
+        .expect("method name should not be registered");
+    module
+}
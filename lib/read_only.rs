@@ -0,0 +1,222 @@
+//! RPC middleware for methods marked with `#[open_api_method(read_only)]`
+
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    future::Future,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use jsonrpsee::{
+    core::middleware::{Batch, Notification, RpcServiceT},
+    types::Request,
+    MethodResponse, ResponsePayload,
+};
+use serde::Deserialize;
+use serde_json::value::RawValue;
+use utoipa::openapi::{path::Operation, OpenApi};
+
+use crate::util::{extension_strs, operations};
+
+#[cfg(feature = "client")]
+mod replica;
+
+#[cfg(feature = "client")]
+pub use replica::{ReplicaRouter, ReplicaRouterLayer};
+
+/// Names of read-only methods in a document that match a filter, including
+/// aliases
+fn read_only_methods(openapi: &OpenApi, filter: impl Fn(&Operation) -> bool) -> HashSet<String> {
+    let mut res = HashSet::new();
+    for (path, operation) in operations(openapi) {
+        let read_only = operation
+            .extensions
+            .as_ref()
+            .and_then(|extensions| extensions.get("x-read-only"))
+            .and_then(|read_only| read_only.as_bool())
+            .unwrap_or(false);
+        if !read_only || !filter(operation) {
+            continue;
+        }
+        let aliases = extension_strs(operation, "x-aliases").unwrap_or_default();
+        res.extend(std::iter::once(path).chain(aliases).map(str::to_owned));
+    }
+    res
+}
+
+/// Returns true if a method requires authentication, ie. its security
+/// requirements, or the document's if it has none, are not empty
+fn authenticated(openapi: &OpenApi, operation: &Operation) -> bool {
+    operation
+        .security
+        .as_ref()
+        .or(openapi.security.as_ref())
+        .is_some_and(|security| !security.is_empty())
+}
+
+/// Successful response for a request, with a result
+fn success_response(request: &Request<'_>, result: Box<RawValue>) -> MethodResponse {
+    // The result was already returned by a server, with its own size limit
+    MethodResponse::response(request.id(), ResponsePayload::success(result), usize::MAX)
+}
+
+/// Result of a successful response
+#[derive(Deserialize)]
+struct Success {
+    result: Box<RawValue>,
+}
+
+/// Cache key for a call, from the method name and params
+type CacheKey = (String, String);
+
+#[derive(Debug, Default)]
+struct CacheEntries {
+    /// Cached results, with the time that they were cached
+    results: HashMap<CacheKey, (Instant, Box<RawValue>)>,
+    /// Keys in the order that they were cached, oldest first. Keys that were
+    /// cached again have an entry for each time.
+    queue: VecDeque<(Instant, CacheKey)>,
+}
+
+impl CacheEntries {
+    /// Remove the oldest entry, if it is still cached
+    fn pop_oldest(&mut self) {
+        let Some((cached_at, key)) = self.queue.pop_front() else {
+            return;
+        };
+        if self
+            .results
+            .get(&key)
+            .is_some_and(|(result_cached_at, _)| *result_cached_at == cached_at)
+        {
+            self.results.remove(&key);
+        }
+    }
+}
+
+#[derive(Debug)]
+struct CacheInner {
+    read_only: HashSet<String>,
+    ttl: Duration,
+    capacity: usize,
+    entries: Mutex<CacheEntries>,
+}
+
+impl CacheInner {
+    fn get(&self, key: &CacheKey) -> Option<Box<RawValue>> {
+        let entries = self.entries.lock().unwrap_or_else(|err| err.into_inner());
+        let (cached_at, result) = entries.results.get(key)?;
+        (cached_at.elapsed() < self.ttl).then(|| result.clone())
+    }
+
+    fn insert(&self, key: CacheKey, result: Box<RawValue>) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut entries = self.entries.lock().unwrap_or_else(|err| err.into_inner());
+        while entries
+            .queue
+            .front()
+            .is_some_and(|(cached_at, _)| cached_at.elapsed() >= self.ttl)
+        {
+            entries.pop_oldest();
+        }
+        while !entries.results.contains_key(&key) && entries.results.len() >= self.capacity {
+            entries.pop_oldest();
+        }
+        let cached_at = Instant::now();
+        entries.queue.push_back((cached_at, key.clone()));
+        entries.results.insert(key, (cached_at, result));
+    }
+}
+
+/// Layer for [`ResponseCache`]
+#[derive(Clone, Debug)]
+pub struct ResponseCacheLayer(Arc<CacheInner>);
+
+impl ResponseCacheLayer {
+    /// Cache successful responses to the read-only methods in a document,
+    /// for a duration, keeping at most `capacity` responses. The oldest
+    /// responses are evicted first.
+    pub fn new(openapi: &OpenApi, ttl: Duration, capacity: usize) -> Self {
+        Self(Arc::new(CacheInner {
+            read_only: read_only_methods(openapi, |operation| !authenticated(openapi, operation)),
+            ttl,
+            capacity,
+            entries: Mutex::new(CacheEntries::default()),
+        }))
+    }
+}
+
+impl<S> tower::Layer<S> for ResponseCacheLayer {
+    type Service = ResponseCache<S>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        ResponseCache {
+            inner: self.0.clone(),
+            service,
+        }
+    }
+}
+
+/// RPC middleware that caches successful responses to read-only methods.
+/// Calls are cached by method name and params, so aliases and params that
+/// are formatted differently are cached separately.
+/// Batches and notifications are not cached.
+///
+/// Methods that require authentication, ie. with `security` requirements,
+/// are not cached, since responses are not keyed by the caller and could be
+/// returned to other callers.
+#[derive(Clone, Debug)]
+pub struct ResponseCache<S> {
+    inner: Arc<CacheInner>,
+    service: S,
+}
+
+impl<S> RpcServiceT for ResponseCache<S>
+where
+    S: RpcServiceT<MethodResponse = MethodResponse> + Send + Sync + Clone + 'static,
+{
+    type MethodResponse = S::MethodResponse;
+    type NotificationResponse = S::NotificationResponse;
+    type BatchResponse = S::BatchResponse;
+
+    fn call<'a>(
+        &self,
+        request: Request<'a>,
+    ) -> impl Future<Output = Self::MethodResponse> + Send + 'a {
+        let inner = self.inner.clone();
+        let service = self.service.clone();
+        async move {
+            if !inner.read_only.contains(request.method_name()) {
+                return service.call(request).await;
+            }
+            let params = request.params.as_ref().map_or("", |params| params.get());
+            let key = (request.method_name().to_owned(), params.to_owned());
+            if let Some(result) = inner.get(&key) {
+                return success_response(&request, result);
+            }
+            let response = service.call(request).await;
+            if response.is_success() {
+                if let Ok(Success { result }) = serde_json::from_str(response.as_json().get()) {
+                    inner.insert(key, result);
+                }
+            }
+            response
+        }
+    }
+
+    fn batch<'a>(
+        &self,
+        requests: Batch<'a>,
+    ) -> impl Future<Output = Self::BatchResponse> + Send + 'a {
+        self.service.batch(requests)
+    }
+
+    fn notification<'a>(
+        &self,
+        n: Notification<'a>,
+    ) -> impl Future<Output = Self::NotificationResponse> + Send + 'a {
+        self.service.notification(n)
+    }
+}
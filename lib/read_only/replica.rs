@@ -0,0 +1,135 @@
+//! RPC middleware that routes read-only calls to a replica
+
+use std::{collections::HashSet, future::Future, sync::Arc};
+
+use jsonrpsee::{
+    core::{
+        client::{ClientT, Error as ClientError},
+        middleware::{Batch, Notification, RpcServiceT},
+        traits::ToRpcParams,
+    },
+    types::Request,
+    MethodResponse,
+};
+use serde_json::value::RawValue;
+use utoipa::openapi::OpenApi;
+
+use super::{read_only_methods, success_response};
+
+/// Params of a request, forwarded as-is
+struct RawParams(Option<Box<RawValue>>);
+
+impl ToRpcParams for RawParams {
+    fn to_rpc_params(self) -> Result<Option<Box<RawValue>>, serde_json::Error> {
+        Ok(self.0)
+    }
+}
+
+#[derive(Debug)]
+struct ReplicaInner<C> {
+    read_only: HashSet<String>,
+    replica: C,
+}
+
+/// Layer for [`ReplicaRouter`]
+#[derive(Debug)]
+pub struct ReplicaRouterLayer<C>(Arc<ReplicaInner<C>>);
+
+impl<C> Clone for ReplicaRouterLayer<C> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<C> ReplicaRouterLayer<C> {
+    /// Route calls to the read-only methods in a document to a replica, eg.
+    /// an HTTP client for a replica node
+    pub fn new(openapi: &OpenApi, replica: C) -> Self {
+        Self(Arc::new(ReplicaInner {
+            read_only: read_only_methods(openapi, |_| true),
+            replica,
+        }))
+    }
+}
+
+impl<S, C> tower::Layer<S> for ReplicaRouterLayer<C> {
+    type Service = ReplicaRouter<S, C>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        ReplicaRouter {
+            inner: self.0.clone(),
+            service,
+        }
+    }
+}
+
+/// RPC middleware that routes calls to read-only methods to a replica.
+/// If the replica cannot be reached, calls are handled by the inner service.
+/// Batches and notifications are always handled by the inner service.
+#[derive(Debug)]
+pub struct ReplicaRouter<S, C> {
+    inner: Arc<ReplicaInner<C>>,
+    service: S,
+}
+
+impl<S, C> Clone for ReplicaRouter<S, C>
+where
+    S: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            service: self.service.clone(),
+        }
+    }
+}
+
+impl<S, C> RpcServiceT for ReplicaRouter<S, C>
+where
+    S: RpcServiceT<MethodResponse = MethodResponse> + Send + Sync + Clone + 'static,
+    C: ClientT + Send + Sync + 'static,
+{
+    type MethodResponse = S::MethodResponse;
+    type NotificationResponse = S::NotificationResponse;
+    type BatchResponse = S::BatchResponse;
+
+    fn call<'a>(
+        &self,
+        request: Request<'a>,
+    ) -> impl Future<Output = Self::MethodResponse> + Send + 'a {
+        let inner = self.inner.clone();
+        let service = self.service.clone();
+        async move {
+            if !inner.read_only.contains(request.method_name()) {
+                return service.call(request).await;
+            }
+            let params = request
+                .params
+                .as_ref()
+                .map(|params| params.clone().into_owned());
+            let res = inner
+                .replica
+                .request::<Box<RawValue>, _>(request.method_name(), RawParams(params))
+                .await;
+            match res {
+                Ok(result) => success_response(&request, result),
+                Err(ClientError::Call(err)) => MethodResponse::error(request.id(), err),
+                Err(_) => service.call(request).await,
+            }
+        }
+    }
+
+    fn batch<'a>(
+        &self,
+        requests: Batch<'a>,
+    ) -> impl Future<Output = Self::BatchResponse> + Send + 'a {
+        self.service.batch(requests)
+    }
+
+    fn notification<'a>(
+        &self,
+        n: Notification<'a>,
+    ) -> impl Future<Output = Self::NotificationResponse> + Send + 'a {
+        self.service.notification(n)
+    }
+}
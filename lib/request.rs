@@ -0,0 +1,120 @@
+//! Helpers for the `{Trait}Request` and `{Trait}Response` enums generated by
+//! `#[open_api(request_enums)]`
+
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{Map, Value};
+
+fn custom_err(msg: impl std::fmt::Display) -> serde_json::Error {
+    <serde_json::Error as serde::de::Error>::custom(msg)
+}
+
+/// Params of a call, that are read in order
+pub struct CallParams {
+    /// Positional params, or `None` if params are named
+    positional: Option<std::vec::IntoIter<Value>>,
+    named: Map<String, Value>,
+}
+
+impl CallParams {
+    /// Params must be an array if positional, or an object if named.
+    /// `null` is treated as no params.
+    pub fn new(method: &str, params: Value, named: bool) -> Result<Self, serde_json::Error> {
+        match (params, named) {
+            (Value::Null, false) => Ok(Self {
+                positional: Some(Vec::new().into_iter()),
+                named: Map::new(),
+            }),
+            (Value::Array(params), false) => Ok(Self {
+                positional: Some(params.into_iter()),
+                named: Map::new(),
+            }),
+            (Value::Null, true) => Ok(Self {
+                positional: None,
+                named: Map::new(),
+            }),
+            (Value::Object(params), true) => Ok(Self {
+                positional: None,
+                named: params,
+            }),
+            (_, false) => Err(custom_err(format!(
+                "expected an array of params for `{method}`"
+            ))),
+            (_, true) => Err(custom_err(format!(
+                "expected an object of params for `{method}`"
+            ))),
+        }
+    }
+
+    /// Read the next param. Missing params are read as `null`.
+    pub fn next<T>(&mut self, name: &str) -> Result<T, serde_json::Error>
+    where
+        T: DeserializeOwned,
+    {
+        let value = match &mut self.positional {
+            Some(positional) => positional.next(),
+            None => self.named.remove(name),
+        };
+        serde_json::from_value(value.unwrap_or_default())
+            .map_err(|err| custom_err(format!("invalid param `{name}`: {err}")))
+    }
+
+    /// Check that there are no unread params
+    pub fn finish(mut self, method: &str) -> Result<(), serde_json::Error> {
+        let extra = match &mut self.positional {
+            Some(positional) => positional.len(),
+            None => self.named.len(),
+        };
+        if extra == 0 {
+            Ok(())
+        } else {
+            Err(custom_err(format!("too many params for `{method}`")))
+        }
+    }
+}
+
+/// Serialize params, as an array if positional, or an object if named
+pub fn call_params(
+    params: Vec<(&str, Result<Value, serde_json::Error>)>,
+    named: bool,
+) -> Result<Value, serde_json::Error> {
+    if named {
+        params
+            .into_iter()
+            .map(|(name, value)| Ok((name.to_owned(), value?)))
+            .collect::<Result<Map<_, _>, _>>()
+            .map(Value::Object)
+    } else {
+        params
+            .into_iter()
+            .map(|(_, value)| value)
+            .collect::<Result<Vec<_>, _>>()
+            .map(Value::Array)
+    }
+}
+
+/// Serialize a call as a `{"method": .., "params": ..}` object
+pub fn call_value(method: &str, params: Value) -> Value {
+    serde_json::json!({ "method": method, "params": params })
+}
+
+/// Split a `{"method": .., "params": ..}` object into the method and params
+pub fn split_call(mut call: Value) -> Result<(String, Value), serde_json::Error> {
+    let Some(Value::String(method)) = call.get_mut("method").map(Value::take) else {
+        return Err(custom_err("expected a `method` string"));
+    };
+    let params = call.get_mut("params").map(Value::take).unwrap_or_default();
+    Ok((method, params))
+}
+
+/// Serialize a value to JSON
+pub fn to_value<T>(value: &T) -> Result<Value, serde_json::Error>
+where
+    T: Serialize + ?Sized,
+{
+    serde_json::to_value(value)
+}
+
+/// Error for an unknown method
+pub fn unknown_method(method: &str) -> serde_json::Error {
+    custom_err(format!("unknown method `{method}`"))
+}
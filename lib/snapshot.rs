@@ -0,0 +1,169 @@
+//! Compare documents with checked-in snapshots
+
+use std::path::{Path, PathBuf};
+
+use serde_json::{Map, Value};
+use utoipa::openapi::OpenApi;
+
+use crate::util::escape_pointer_segment;
+
+/// If this environment variable is set to `1`, snapshots are written instead
+/// of checked
+pub const BLESS_ENV_VAR: &str = "L2L_OPENAPI_BLESS";
+
+/// Error when checking a snapshot
+#[derive(Debug)]
+pub enum SnapshotError {
+    /// The snapshot file does not exist
+    Missing(PathBuf),
+    /// The snapshot does not match the document
+    Mismatch {
+        path: PathBuf,
+        /// JSON pointer to the first difference
+        pointer: String,
+    },
+    /// The snapshot file is not valid JSON
+    Parse {
+        path: PathBuf,
+        err: serde_json::Error,
+    },
+    Io {
+        path: PathBuf,
+        err: std::io::Error,
+    },
+}
+
+impl std::fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Missing(path) => write!(
+                f,
+                "snapshot `{}` does not exist; run with {BLESS_ENV_VAR}=1 to create it",
+                path.display()
+            ),
+            Self::Mismatch { path, pointer } => {
+                let pointer = if pointer.is_empty() { "/" } else { pointer };
+                write!(
+                    f,
+                    "document does not match snapshot `{}` at `{pointer}`; run with {BLESS_ENV_VAR}=1 to update it",
+                    path.display()
+                )
+            }
+            Self::Parse { path, err } => {
+                write!(f, "failed to parse snapshot `{}`: {err}", path.display())
+            }
+            Self::Io { path, err } => {
+                write!(f, "failed to access snapshot `{}`: {err}", path.display())
+            }
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Missing(_) | Self::Mismatch { .. } => None,
+            Self::Parse { err, .. } => Some(err),
+            Self::Io { err, .. } => Some(err),
+        }
+    }
+}
+
+/// Sort object keys, recursively
+fn canonicalize(value: Value) -> Value {
+    match value {
+        Value::Array(items) => Value::Array(items.into_iter().map(canonicalize).collect()),
+        Value::Object(map) => {
+            let mut entries = Vec::from_iter(map);
+            entries.sort_by(|(lhs, _), (rhs, _)| lhs.cmp(rhs));
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key, canonicalize(value)))
+                    .collect::<Map<_, _>>(),
+            )
+        }
+        value => value,
+    }
+}
+
+/// Canonical JSON for a document, with sorted keys
+pub fn canonical_json(openapi: &OpenApi) -> String {
+    let value = serde_json::to_value(openapi).expect("document should serialize");
+    let mut res =
+        serde_json::to_string_pretty(&canonicalize(value)).expect("JSON should serialize");
+    res.push('\n');
+    res
+}
+
+/// JSON pointer to the first difference between two values
+fn first_difference(lhs: &Value, rhs: &Value) -> Option<String> {
+    match (lhs, rhs) {
+        (Value::Array(lhs_items), Value::Array(rhs_items))
+            if lhs_items.len() == rhs_items.len() =>
+        {
+            lhs_items
+                .iter()
+                .zip(rhs_items)
+                .enumerate()
+                .find_map(|(idx, (lhs, rhs))| {
+                    first_difference(lhs, rhs).map(|pointer| format!("/{idx}{pointer}"))
+                })
+        }
+        (Value::Object(lhs_map), Value::Object(rhs_map)) => {
+            let mut keys = Vec::from_iter(lhs_map.keys().chain(rhs_map.keys()));
+            keys.sort();
+            keys.dedup();
+            keys.into_iter().find_map(|key| {
+                let segment = escape_pointer_segment(key);
+                match (lhs_map.get(key), rhs_map.get(key)) {
+                    (Some(lhs), Some(rhs)) => {
+                        first_difference(lhs, rhs).map(|pointer| format!("/{segment}{pointer}"))
+                    }
+                    _ => Some(format!("/{segment}")),
+                }
+            })
+        }
+        (lhs, rhs) if lhs == rhs => None,
+        _ => Some(String::new()),
+    }
+}
+
+/// Compare a document with a snapshot file, using canonical JSON.
+/// If [`BLESS_ENV_VAR`] is set to `1`, the snapshot is written instead.
+pub fn check_snapshot<P>(openapi: &OpenApi, path: P) -> Result<(), SnapshotError>
+where
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    let io_err = |err| SnapshotError::Io {
+        path: path.to_owned(),
+        err,
+    };
+    let json = canonical_json(openapi);
+    if std::env::var(BLESS_ENV_VAR).is_ok_and(|bless| bless == "1") {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(io_err)?;
+        }
+        return std::fs::write(path, json).map_err(io_err);
+    }
+    let snapshot = match std::fs::read_to_string(path) {
+        Ok(snapshot) => snapshot,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            return Err(SnapshotError::Missing(path.to_owned()))
+        }
+        Err(err) => return Err(io_err(err)),
+    };
+    let snapshot: Value = serde_json::from_str(&snapshot).map_err(|err| SnapshotError::Parse {
+        path: path.to_owned(),
+        err,
+    })?;
+    let value = serde_json::to_value(openapi).expect("document should serialize");
+    match first_difference(&snapshot, &value) {
+        None => Ok(()),
+        Some(pointer) => Err(SnapshotError::Mismatch {
+            path: path.to_owned(),
+            pointer,
+        }),
+    }
+}
@@ -0,0 +1,280 @@
+//! Test RPC servers against documents
+
+use jsonrpsee::core::{
+    client::ClientT,
+    params::{ArrayParams, ObjectParams},
+    traits::ToRpcParams,
+    ClientError,
+};
+use serde_json::{value::RawValue, Value};
+use utoipa::openapi::{path::Operation, OpenApi, RefOr};
+
+use crate::{
+    json_schema::{SchemaError, Validator},
+    util::{escape_pointer_segment, extension_strs, operations},
+};
+
+mod fuzz;
+
+pub use fuzz::{fuzz, FuzzConfig, FuzzError, FuzzIssue, FuzzReport};
+
+/// Issue found when calling a method with an example
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConformanceIssue {
+    /// The result does not match the documented result schema
+    InvalidResult {
+        method: String,
+        params: Vec<Value>,
+        errors: Vec<SchemaError>,
+    },
+    /// The error does not match the documented error schema
+    InvalidError {
+        method: String,
+        params: Vec<Value>,
+        errors: Vec<SchemaError>,
+    },
+    /// The server rejected the example as an unknown method or invalid params
+    RejectedExample {
+        method: String,
+        params: Vec<Value>,
+        code: i32,
+        message: String,
+    },
+    /// The request failed, other than with an error response
+    RequestFailed {
+        method: String,
+        params: Vec<Value>,
+        error: String,
+    },
+}
+
+impl std::fmt::Display for ConformanceIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let write_errors = |f: &mut std::fmt::Formatter<'_>, errors: &[SchemaError]| {
+            errors
+                .iter()
+                .try_for_each(|error| write!(f, "\n    {error}"))
+        };
+        match self {
+            Self::InvalidResult {
+                method,
+                params,
+                errors,
+            } => {
+                write!(f, "invalid result for `{method}` with params {params:?}:")?;
+                write_errors(f, errors)
+            }
+            Self::InvalidError {
+                method,
+                params,
+                errors,
+            } => {
+                write!(f, "invalid error for `{method}` with params {params:?}:")?;
+                write_errors(f, errors)
+            }
+            Self::RejectedExample {
+                method,
+                params,
+                code,
+                message,
+            } => write!(
+                f,
+                "`{method}` rejected params {params:?} with error {code}: {message}"
+            ),
+            Self::RequestFailed {
+                method,
+                params,
+                error,
+            } => write!(
+                f,
+                "request to `{method}` with params {params:?} failed: {error}"
+            ),
+        }
+    }
+}
+
+/// Error when checking a server against a document
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConformanceError(pub Vec<ConformanceIssue>);
+
+impl std::fmt::Display for ConformanceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "server does not conform to document:")?;
+        self.0.iter().try_for_each(|issue| write!(f, "\n  {issue}"))
+    }
+}
+
+impl std::error::Error for ConformanceError {}
+
+/// Whether an operation takes params by name, from the `x-param-structure`
+/// extension
+fn params_by_name(operation: &Operation) -> bool {
+    operation
+        .extensions
+        .as_ref()
+        .and_then(|extensions| extensions.get("x-param-structure"))
+        .is_some_and(|structure| structure == "by-name")
+}
+
+/// Params for a call, by position or by name
+enum CallParams {
+    ByPosition(ArrayParams),
+    ByName(ObjectParams),
+}
+
+impl CallParams {
+    /// Params for a call to a method. Params are sent by name, as an object
+    /// keyed by `x-param-names`, if the method takes params by name.
+    fn new(operation: &Operation, params: &[Value]) -> Self {
+        let param_names = extension_strs(operation, "x-param-names").unwrap_or_default();
+        if params_by_name(operation) {
+            let mut object_params = ObjectParams::new();
+            for (name, param) in param_names.iter().zip(params) {
+                object_params
+                    .insert(name, param)
+                    .expect("JSON values should serialize");
+            }
+            Self::ByName(object_params)
+        } else {
+            let mut array_params = ArrayParams::new();
+            for param in params {
+                array_params
+                    .insert(param)
+                    .expect("JSON values should serialize");
+            }
+            Self::ByPosition(array_params)
+        }
+    }
+}
+
+impl ToRpcParams for CallParams {
+    fn to_rpc_params(self) -> Result<Option<Box<RawValue>>, serde_json::Error> {
+        match self {
+            Self::ByPosition(params) => params.to_rpc_params(),
+            Self::ByName(params) => params.to_rpc_params(),
+        }
+    }
+}
+
+/// Positional params for a request body value
+fn positional_params(param_names: &[&str], request_body: &Value) -> Vec<Value> {
+    if param_names.len() <= 1 {
+        return vec![request_body.clone()];
+    }
+    let mut params: Vec<_> = param_names
+        .iter()
+        .map(|name| request_body.get(name).cloned())
+        .collect();
+    // Omitted params are trailing optional params
+    while let Some(None) = params.last() {
+        params.pop();
+    }
+    params
+        .into_iter()
+        .map(|param| param.unwrap_or_default())
+        .collect()
+}
+
+/// Example params for each method in a document.
+/// Methods without params are called once, with no params.
+/// Methods with params are called once for each request body example.
+fn example_calls(openapi: &OpenApi) -> Vec<(&str, &Operation, Vec<Vec<Value>>)> {
+    operations(openapi)
+        .map(|(path, operation)| {
+            let Some(request_body) = &operation.request_body else {
+                return (path, operation, vec![Vec::new()]);
+            };
+            let param_names = extension_strs(operation, "x-param-names").unwrap_or_default();
+            let calls = request_body
+                .content
+                .get("application/json")
+                .into_iter()
+                .flat_map(|content| content.examples.values())
+                .filter_map(|example| match example {
+                    RefOr::T(example) => example.value.as_ref(),
+                    RefOr::Ref(_) => None,
+                })
+                .map(|value| positional_params(&param_names, value))
+                .collect();
+            (path, operation, calls)
+        })
+        .collect()
+}
+
+/// Call each documented method with its examples, and check that results and
+/// errors match the documented schemas.
+/// Results are validated against the `200` response schema, and errors
+/// against the `default` response schema, if they are documented.
+/// Methods with params, but without examples, are not called.
+/// Params are sent by name for methods with `param_kind = map`.
+pub async fn check_conformance<C>(client: &C, openapi: &OpenApi) -> Result<(), ConformanceError>
+where
+    C: ClientT + Sync,
+{
+    let doc = serde_json::to_value(openapi).expect("document should serialize");
+    let validator = Validator::new(&doc);
+    let mut issues = Vec::new();
+    for (method, operation, calls) in example_calls(openapi) {
+        let responses_path = format!("/paths/{}/post/responses", escape_pointer_segment(method));
+        let schema_path = |response: &str| {
+            format!("{responses_path}/{response}/content/application~1json/schema")
+        };
+        let result_schema_path = schema_path("200");
+        let error_schema_path = schema_path("default");
+        for params in calls {
+            let call_params = CallParams::new(operation, &params);
+            let mut errors = Vec::new();
+            match client.request::<Value, _>(method, call_params).await {
+                Ok(result) => {
+                    if doc.pointer(&result_schema_path).is_some() {
+                        validator.validate_at(&result_schema_path, &result, "", &mut errors);
+                    }
+                    if !errors.is_empty() {
+                        issues.push(ConformanceIssue::InvalidResult {
+                            method: method.to_owned(),
+                            params,
+                            errors,
+                        });
+                    }
+                }
+                Err(ClientError::Call(error))
+                    if matches!(
+                        error.code(),
+                        jsonrpsee::types::error::METHOD_NOT_FOUND_CODE
+                            | jsonrpsee::types::error::INVALID_PARAMS_CODE
+                    ) =>
+                {
+                    issues.push(ConformanceIssue::RejectedExample {
+                        method: method.to_owned(),
+                        params,
+                        code: error.code(),
+                        message: error.message().to_owned(),
+                    })
+                }
+                Err(ClientError::Call(error)) => {
+                    if doc.pointer(&error_schema_path).is_some() {
+                        let error = serde_json::to_value(&error).expect("errors should serialize");
+                        validator.validate_at(&error_schema_path, &error, "", &mut errors);
+                    }
+                    if !errors.is_empty() {
+                        issues.push(ConformanceIssue::InvalidError {
+                            method: method.to_owned(),
+                            params,
+                            errors,
+                        });
+                    }
+                }
+                Err(error) => issues.push(ConformanceIssue::RequestFailed {
+                    method: method.to_owned(),
+                    params,
+                    error: error.to_string(),
+                }),
+            }
+        }
+    }
+    if issues.is_empty() {
+        Ok(())
+    } else {
+        Err(ConformanceError(issues))
+    }
+}
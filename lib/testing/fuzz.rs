@@ -0,0 +1,520 @@
+//! Schema-driven fuzzing of RPC servers
+
+use jsonrpsee::{
+    core::{client::ClientT, ClientError},
+    types::error::INVALID_PARAMS_CODE,
+};
+use serde_json::{Map, Value};
+use utoipa::openapi::{path::Operation, OpenApi};
+
+use super::CallParams;
+use crate::{
+    json_schema::{integer_format_range, Validator},
+    util::{extension_strs, operations, param_schema_paths},
+};
+
+/// Maximum depth of `$ref`s to follow when generating values
+const MAX_DEPTH: usize = 8;
+
+/// Attempts to generate a value for a schema
+const MAX_ATTEMPTS: usize = 8;
+
+/// Deterministic PRNG (splitmix64), so that failures can be reproduced from
+/// a seed
+struct Rng(u64);
+
+impl Rng {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `0..n`. `n` must be non-zero.
+    fn below(&mut self, n: u64) -> u64 {
+        self.next_u64() % n
+    }
+
+    /// Uniform in `min..=max`
+    fn range_i128(&mut self, min: i128, max: i128) -> i128 {
+        let width = (max - min) as u128 + 1;
+        let r = ((self.next_u64() as u128) << 64) | self.next_u64() as u128;
+        min + (r % width) as i128
+    }
+
+    /// Uniform in `0.0..1.0`
+    fn unit_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    fn bool(&mut self) -> bool {
+        self.next_u64() & 1 == 1
+    }
+
+    fn choose<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        if items.is_empty() {
+            None
+        } else {
+            Some(&items[self.below(items.len() as u64) as usize])
+        }
+    }
+
+    fn shuffle<T>(&mut self, items: &mut [T]) {
+        for idx in (1..items.len()).rev() {
+            let other = self.below(idx as u64 + 1) as usize;
+            items.swap(idx, other);
+        }
+    }
+}
+
+fn integer_value(n: i128) -> Value {
+    if let Ok(n) = i64::try_from(n) {
+        Value::from(n)
+    } else if let Ok(n) = u64::try_from(n) {
+        Value::from(n)
+    } else {
+        Value::from(n as f64)
+    }
+}
+
+/// Values that are likely to be invalid for a schema
+fn invalid_candidates() -> Vec<Value> {
+    vec![
+        Value::Null,
+        Value::Bool(true),
+        Value::from(0.5),
+        Value::from(-1),
+        Value::from(u64::MAX),
+        Value::from(""),
+        Value::from("fuzz"),
+        Value::Array(Vec::new()),
+        Value::Array(vec![Value::Null]),
+        Value::Object(Map::new()),
+        serde_json::json!({ "fuzz": null }),
+    ]
+}
+
+/// Generates values from schemas
+struct Generator<'a> {
+    validator: Validator<'a>,
+    rng: Rng,
+}
+
+impl Generator<'_> {
+    /// Generate a value that is valid for a schema.
+    /// Returns `None` if no valid value could be generated, eg. for schemas
+    /// with a `pattern`.
+    fn valid(&mut self, schema: &Value, depth: usize) -> Option<Value> {
+        if depth > MAX_DEPTH {
+            return None;
+        }
+        (0..MAX_ATTEMPTS).find_map(|_| {
+            let value = self.candidate(schema, depth)?;
+            self.validator.is_valid(schema, "", &value).then_some(value)
+        })
+    }
+
+    /// Generate a value that is likely to be valid for a schema
+    fn candidate(&mut self, schema: &Value, depth: usize) -> Option<Value> {
+        let schema = match schema {
+            Value::Bool(true) => return Some(Value::Null),
+            Value::Object(schema) => schema,
+            _ => return None,
+        };
+        if let Some(Value::String(reference)) = schema.get("$ref") {
+            let target = self.validator.resolve(reference)?;
+            return self.valid(target, depth + 1);
+        }
+        if let Some(Value::Array(variants)) = schema.get("enum") {
+            return self.rng.choose(variants).cloned();
+        }
+        if let Some(value) = schema.get("const") {
+            return Some(value.clone());
+        }
+        for keyword in ["oneOf", "anyOf", "allOf"] {
+            if let Some(Value::Array(subschemas)) = schema.get(keyword) {
+                let mut subschemas = Vec::from_iter(subschemas);
+                self.rng.shuffle(&mut subschemas);
+                return subschemas
+                    .into_iter()
+                    .find_map(|subschema| self.valid(subschema, depth + 1));
+            }
+        }
+        let ty = match schema.get("type") {
+            Some(Value::String(ty)) => ty.as_str(),
+            Some(Value::Array(tys)) => self.rng.choose(tys)?.as_str()?,
+            None if schema.contains_key("properties") => "object",
+            None => return Some(Value::Null),
+            Some(_) => return None,
+        };
+        match ty {
+            "null" => Some(Value::Null),
+            "boolean" => Some(Value::Bool(self.rng.bool())),
+            "integer" => self.integer(schema),
+            "number" => self.number(schema),
+            "string" => self.string(schema),
+            "array" => self.array(schema, depth),
+            "object" => self.object(schema, depth),
+            _ => None,
+        }
+    }
+
+    fn integer(&mut self, schema: &Map<String, Value>) -> Option<Value> {
+        let (mut min, mut max) = schema
+            .get("format")
+            .and_then(Value::as_str)
+            .and_then(integer_format_range)
+            .unwrap_or((i64::MIN as i128, i64::MAX as i128));
+        let bound = |keyword: &str| schema.get(keyword).and_then(Value::as_f64);
+        if let Some(minimum) = bound("minimum") {
+            min = min.max(minimum.ceil() as i128);
+        }
+        if let Some(minimum) = bound("exclusiveMinimum") {
+            min = min.max(minimum.floor() as i128 + 1);
+        }
+        if let Some(maximum) = bound("maximum") {
+            max = max.min(maximum.floor() as i128);
+        }
+        if let Some(maximum) = bound("exclusiveMaximum") {
+            max = max.min(maximum.ceil() as i128 - 1);
+        }
+        if min > max {
+            return None;
+        }
+        // Bias towards bounds and small values
+        let (small_min, small_max) = (min.max(-16), max.min(16));
+        let n = match self.rng.below(4) {
+            0 => min,
+            1 => max,
+            2 if small_min <= small_max => self.rng.range_i128(small_min, small_max),
+            _ => self.rng.range_i128(min, max),
+        };
+        Some(integer_value(n))
+    }
+
+    fn number(&mut self, schema: &Map<String, Value>) -> Option<Value> {
+        let bound = |keyword: &str| schema.get(keyword).and_then(Value::as_f64);
+        let min = bound("minimum")
+            .or(bound("exclusiveMinimum"))
+            .unwrap_or(-1e6);
+        let max = bound("maximum")
+            .or(bound("exclusiveMaximum"))
+            .unwrap_or(1e6);
+        if min > max {
+            return None;
+        }
+        let n = min + (max - min) * self.rng.unit_f64();
+        serde_json::Number::from_f64(n).map(Value::Number)
+    }
+
+    fn string(&mut self, schema: &Map<String, Value>) -> Option<Value> {
+        // Patterns and string formats are not supported
+        if schema.contains_key("pattern") || schema.contains_key("format") {
+            return None;
+        }
+        const CHARS: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789";
+        let min_len = schema.get("minLength").and_then(Value::as_u64).unwrap_or(0);
+        let max_len = schema
+            .get("maxLength")
+            .and_then(Value::as_u64)
+            .unwrap_or(u64::MAX)
+            .min(min_len + 16);
+        if min_len > max_len {
+            return None;
+        }
+        let len = min_len + self.rng.below(max_len - min_len + 1);
+        let string = (0..len)
+            .map(|_| *self.rng.choose(CHARS).unwrap() as char)
+            .collect::<String>();
+        Some(Value::String(string))
+    }
+
+    fn array(&mut self, schema: &Map<String, Value>, depth: usize) -> Option<Value> {
+        let prefix_items = match schema.get("prefixItems") {
+            Some(Value::Array(prefix_items)) => prefix_items.as_slice(),
+            _ => &[],
+        };
+        let items_schema = schema.get("items");
+        let min_items = schema.get("minItems").and_then(Value::as_u64).unwrap_or(0);
+        let max_items = match items_schema {
+            Some(Value::Bool(false)) => prefix_items.len() as u64,
+            _ => schema
+                .get("maxItems")
+                .and_then(Value::as_u64)
+                .unwrap_or(u64::MAX)
+                .min(min_items.max(prefix_items.len() as u64) + 4),
+        };
+        if min_items > max_items {
+            return None;
+        }
+        let len = min_items + self.rng.below(max_items - min_items + 1);
+        (0..len as usize)
+            .map(|idx| match prefix_items.get(idx).or(items_schema) {
+                Some(item_schema) => self.valid(item_schema, depth + 1),
+                None => Some(Value::Null),
+            })
+            .collect::<Option<Vec<_>>>()
+            .map(Value::Array)
+    }
+
+    fn object(&mut self, schema: &Map<String, Value>, depth: usize) -> Option<Value> {
+        let required: Vec<&str> = match schema.get("required") {
+            Some(Value::Array(required)) => required.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        let mut object = Map::new();
+        if let Some(Value::Object(properties)) = schema.get("properties") {
+            for (name, property_schema) in properties {
+                let is_required = required.contains(&name.as_str());
+                if !is_required && self.rng.bool() {
+                    continue;
+                }
+                match self.valid(property_schema, depth + 1) {
+                    Some(value) => {
+                        object.insert(name.clone(), value);
+                    }
+                    None if is_required => return None,
+                    None => (),
+                }
+            }
+        }
+        Some(Value::Object(object))
+    }
+
+    /// Generate a value that is invalid for a schema
+    fn invalid(&mut self, schema: &Value) -> Option<Value> {
+        let mut candidates = invalid_candidates();
+        self.rng.shuffle(&mut candidates);
+        candidates
+            .into_iter()
+            .find(|candidate| !self.validator.is_valid(schema, "", candidate))
+    }
+}
+
+/// Configuration for [`fuzz`]
+#[derive(Clone, Debug)]
+pub struct FuzzConfig {
+    /// Seed for generated params
+    pub seed: u64,
+    /// Number of valid, and invalid, calls for each method
+    pub iterations: usize,
+}
+
+impl Default for FuzzConfig {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            iterations: 32,
+        }
+    }
+}
+
+/// Issue found when fuzzing a method
+#[derive(Clone, Debug, PartialEq)]
+pub enum FuzzIssue {
+    /// Params that are valid for the documented schemas were rejected as
+    /// invalid params
+    ValidRejected {
+        method: String,
+        params: Vec<Value>,
+        message: String,
+    },
+    /// Params that are invalid for the documented schemas were not rejected
+    /// as invalid params
+    InvalidAccepted { method: String, params: Vec<Value> },
+    /// The request failed, other than with an error response
+    RequestFailed {
+        method: String,
+        params: Vec<Value>,
+        error: String,
+    },
+}
+
+impl std::fmt::Display for FuzzIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ValidRejected {
+                method,
+                params,
+                message,
+            } => write!(
+                f,
+                "`{method}` rejected valid params {}: {message}",
+                Value::Array(params.clone())
+            ),
+            Self::InvalidAccepted { method, params } => write!(
+                f,
+                "`{method}` did not reject invalid params {}",
+                Value::Array(params.clone())
+            ),
+            Self::RequestFailed {
+                method,
+                params,
+                error,
+            } => write!(
+                f,
+                "request to `{method}` with params {} failed: {error}",
+                Value::Array(params.clone())
+            ),
+        }
+    }
+}
+
+/// Methods that were fuzzed, returned whether or not issues were found
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FuzzReport {
+    /// Methods that were not called, because params could not be generated
+    /// for their schemas, eg. strings with a `pattern`
+    pub skipped: Vec<String>,
+}
+
+/// Error when fuzzing a server
+#[derive(Clone, Debug, PartialEq)]
+pub struct FuzzError {
+    pub issues: Vec<FuzzIssue>,
+    pub report: FuzzReport,
+}
+
+impl std::fmt::Display for FuzzError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "fuzzing found issues:")?;
+        self.issues
+            .iter()
+            .try_for_each(|issue| write!(f, "\n  {issue}"))?;
+        if !self.report.skipped.is_empty() {
+            write!(f, "\nskipped methods: {}", self.report.skipped.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for FuzzError {}
+
+/// Result of a call, for fuzzing
+enum Outcome {
+    InvalidParams(String),
+    Other,
+    Failed(String),
+}
+
+async fn call<C>(client: &C, method: &str, operation: &Operation, params: &[Value]) -> Outcome
+where
+    C: ClientT + Sync,
+{
+    let call_params = CallParams::new(operation, params);
+    match client.request::<Value, _>(method, call_params).await {
+        Ok(_) => Outcome::Other,
+        Err(ClientError::Call(err)) if err.code() == INVALID_PARAMS_CODE => {
+            Outcome::InvalidParams(err.message().to_owned())
+        }
+        Err(ClientError::Call(_)) => Outcome::Other,
+        Err(err) => Outcome::Failed(err.to_string()),
+    }
+}
+
+/// Call each documented method with random params generated from the
+/// documented schemas.
+/// Params that are valid for the schemas should never be rejected with an
+/// `Invalid params` error, and params that are invalid should always be.
+/// At most one issue of each kind is reported for each method.
+///
+/// Methods are called with arbitrary params, so this should only be used
+/// with a local server.
+/// Params are sent by name for methods with `param_kind = map`.
+/// Methods with params that no value can be generated for, eg. strings with
+/// a `pattern`, are listed in [`FuzzReport::skipped`], which is also
+/// returned with any issues.
+pub async fn fuzz<C>(
+    client: &C,
+    openapi: &OpenApi,
+    config: &FuzzConfig,
+) -> Result<FuzzReport, FuzzError>
+where
+    C: ClientT + Sync,
+{
+    let doc = serde_json::to_value(openapi).expect("document should serialize");
+    let mut generator = Generator {
+        validator: Validator::new(&doc),
+        rng: Rng(config.seed),
+    };
+    let mut issues = Vec::new();
+    let mut report = FuzzReport::default();
+    for (method, operation) in operations(openapi) {
+        if operation.request_body.is_none() {
+            continue;
+        }
+        let param_names = extension_strs(operation, "x-param-names").unwrap_or_default();
+        let param_schemas: Vec<&Value> = param_schema_paths(&doc, method, &param_names)
+            .iter()
+            .filter_map(|schema_path| doc.pointer(schema_path))
+            .collect();
+        if param_names.is_empty() || param_schemas.len() != param_names.len() {
+            report.skipped.push(method.to_owned());
+            continue;
+        }
+        let (mut valid_rejected, mut invalid_accepted) = (false, false);
+        for iteration in 0..config.iterations {
+            let Some(params) = param_schemas
+                .iter()
+                .map(|schema| generator.valid(schema, 0))
+                .collect::<Option<Vec<_>>>()
+            else {
+                if iteration == 0 {
+                    report.skipped.push(method.to_owned());
+                }
+                break;
+            };
+            match call(client, method, operation, &params).await {
+                Outcome::InvalidParams(message) if !valid_rejected => {
+                    valid_rejected = true;
+                    issues.push(FuzzIssue::ValidRejected {
+                        method: method.to_owned(),
+                        params: params.clone(),
+                        message,
+                    })
+                }
+                Outcome::Failed(error) => {
+                    issues.push(FuzzIssue::RequestFailed {
+                        method: method.to_owned(),
+                        params,
+                        error,
+                    });
+                    break;
+                }
+                Outcome::InvalidParams(_) | Outcome::Other => (),
+            }
+            // Replace one param with an invalid value
+            let mut params = params;
+            let idx = generator.rng.below(params.len() as u64) as usize;
+            let Some(invalid) = generator.invalid(param_schemas[idx]) else {
+                continue;
+            };
+            params[idx] = invalid;
+            match call(client, method, operation, &params).await {
+                Outcome::Other if !invalid_accepted => {
+                    invalid_accepted = true;
+                    issues.push(FuzzIssue::InvalidAccepted {
+                        method: method.to_owned(),
+                        params,
+                    })
+                }
+                Outcome::Failed(error) => {
+                    issues.push(FuzzIssue::RequestFailed {
+                        method: method.to_owned(),
+                        params,
+                        error,
+                    });
+                    break;
+                }
+                Outcome::InvalidParams(_) | Outcome::Other => (),
+            }
+        }
+    }
+    if issues.is_empty() {
+        Ok(report)
+    } else {
+        Err(FuzzError { issues, report })
+    }
+}
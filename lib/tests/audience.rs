@@ -0,0 +1,115 @@
+#![cfg(all(feature = "gen-doc", feature = "middleware"))]
+
+use jsonrpsee::{
+    core::{client::ClientT, RpcResult},
+    http_client::HttpClient,
+    proc_macros::rpc,
+    rpc_params,
+    server::{middleware::rpc::RpcServiceBuilder, Server, ServerHandle},
+};
+use l2l_openapi::{open_api, AudienceFilterLayer};
+use utoipa::OpenApi;
+
+#[open_api]
+#[rpc(server)]
+pub trait Node {
+    #[method(name = "get_block_count")]
+    async fn get_block_count(&self) -> RpcResult<u32>;
+
+    #[open_api_method(audience = "admin")]
+    #[method(name = "stop", aliases = ["shutdown"])]
+    async fn stop(&self) -> RpcResult<()>;
+
+    #[open_api_method(audience = "operator")]
+    #[method(name = "ban_peer")]
+    async fn ban_peer(&self, addr: String) -> RpcResult<()>;
+}
+
+struct NodeImpl;
+
+#[jsonrpsee::core::async_trait]
+impl NodeServer for NodeImpl {
+    async fn get_block_count(&self) -> RpcResult<u32> {
+        Ok(1)
+    }
+
+    async fn stop(&self) -> RpcResult<()> {
+        Ok(())
+    }
+
+    async fn ban_peer(&self, _addr: String) -> RpcResult<()> {
+        Ok(())
+    }
+}
+
+fn method_names(openapi: &utoipa::openapi::OpenApi) -> Vec<&str> {
+    openapi.paths.paths.keys().map(String::as_str).collect()
+}
+
+#[test]
+fn test_openapi_for() -> anyhow::Result<()> {
+    let doc = serde_json::to_value(NodeDoc::openapi())?;
+    assert_eq!(doc["paths"]["stop"]["post"]["x-audience"], "admin");
+    assert!(doc["paths"]["get_block_count"]["post"]
+        .get("x-audience")
+        .is_none());
+
+    assert_eq!(
+        method_names(&NodeDoc::openapi_for(&["public"])),
+        ["get_block_count"]
+    );
+    assert_eq!(
+        method_names(&NodeDoc::openapi_for(&["admin", "operator"])),
+        ["ban_peer", "stop"]
+    );
+    assert!(method_names(&NodeDoc::openapi_for(&[])).is_empty());
+    Ok(())
+}
+
+/// Start a server with an undocumented method, with the audience filter
+async fn filtered_client(layer: AudienceFilterLayer) -> anyhow::Result<(HttpClient, ServerHandle)> {
+    let server = Server::builder()
+        .set_rpc_middleware(RpcServiceBuilder::new().layer(layer))
+        .build("127.0.0.1:0")
+        .await?;
+    let url = format!("http://{}", server.local_addr()?);
+    let mut rpc_module = NodeImpl.into_rpc();
+    rpc_module.register_method("get_version", |_, _, _| "1.0")?;
+    let handle = server.start(rpc_module);
+    Ok((HttpClient::builder().build(url)?, handle))
+}
+
+/// Returns the error code of a call, if it fails
+async fn call_err_code(client: &HttpClient, method: &str) -> anyhow::Result<Option<i32>> {
+    match client
+        .request::<serde_json::Value, _>(method, rpc_params![])
+        .await
+    {
+        Ok(_) => Ok(None),
+        Err(jsonrpsee::core::ClientError::Call(err)) => Ok(Some(err.code())),
+        Err(err) => Err(err.into()),
+    }
+}
+
+#[tokio::test]
+async fn test_audience_filter() -> anyhow::Result<()> {
+    let layer = AudienceFilterLayer::new(&NodeDoc::openapi(), &["public", "operator"]);
+    let (client, handle) = filtered_client(layer).await?;
+    let count: u32 = client.request("get_block_count", rpc_params![]).await?;
+    assert_eq!(count, 1);
+    client
+        .request::<(), _>("ban_peer", rpc_params!["127.0.0.1"])
+        .await?;
+    // Undocumented methods are rejected
+    for method in ["stop", "shutdown", "get_version"] {
+        assert_eq!(call_err_code(&client, method).await?, Some(-32601));
+    }
+    handle.stop()?;
+
+    let layer = AudienceFilterLayer::new(&NodeDoc::openapi(), &["public"]).allow_undocumented();
+    let (client, handle) = filtered_client(layer).await?;
+    assert_eq!(call_err_code(&client, "get_version").await?, None);
+    assert_eq!(call_err_code(&client, "stop").await?, Some(-32601));
+    handle.stop()?;
+    Ok(())
+}
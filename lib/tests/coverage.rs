@@ -0,0 +1,61 @@
+#![cfg(feature = "gen-doc")]
+
+use jsonrpsee::{
+    core::{RpcResult, SubscriptionResult},
+    proc_macros::rpc,
+    PendingSubscriptionSink,
+};
+use l2l_openapi::{check_coverage, open_api};
+use utoipa::OpenApi;
+
+#[open_api]
+#[rpc(server, namespace = "node")]
+pub trait Node {
+    #[method(name = "height", aliases = ["getblockcount"])]
+    async fn height(&self) -> RpcResult<u32>;
+
+    #[method(name = "echo")]
+    async fn echo(&self, msg: String) -> RpcResult<String>;
+
+    #[subscription(name = "subscribe_height", unsubscribe = "unsubscribe_height", item = u32)]
+    async fn subscribe_height(&self) -> SubscriptionResult;
+}
+
+struct Server;
+
+#[jsonrpsee::core::async_trait]
+impl NodeServer for Server {
+    async fn height(&self) -> RpcResult<u32> {
+        Ok(0)
+    }
+
+    async fn echo(&self, msg: String) -> RpcResult<String> {
+        Ok(msg)
+    }
+
+    async fn subscribe_height(&self, pending: PendingSubscriptionSink) -> SubscriptionResult {
+        pending.accept().await?;
+        Ok(())
+    }
+}
+
+#[test]
+fn test_check_coverage() -> anyhow::Result<()> {
+    let mut rpc_module = Server.into_rpc();
+    let mut openapi = NodeDoc::openapi();
+    // Subscription and unsubscription methods are ignored
+    assert!(rpc_module
+        .method_names()
+        .any(|name| name == "node_subscribe_height"));
+    check_coverage(&rpc_module, &openapi)?;
+
+    rpc_module.register_method("hand_registered", |_, _, _| 0u32)?;
+    openapi.paths.paths.remove("node_echo");
+    let err = check_coverage(&rpc_module, &openapi).unwrap_err();
+    assert_eq!(
+        Vec::from_iter(err.undocumented),
+        ["hand_registered", "node_echo"]
+    );
+    assert!(err.unregistered.is_empty());
+    Ok(())
+}
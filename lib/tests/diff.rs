@@ -0,0 +1,149 @@
+#![cfg(feature = "gen-doc")]
+
+use l2l_openapi::{diff, diff_json, Change, Compat};
+use serde_json::{json, Value};
+use utoipa::OpenApi;
+
+mod v1 {
+    use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+    use l2l_openapi::open_api;
+
+    #[open_api]
+    #[rpc(server)]
+    pub trait Node {
+        #[method(name = "get_block_hash")]
+        async fn get_block_hash(&self, height: u32) -> RpcResult<String>;
+
+        #[method(name = "get_peers")]
+        async fn get_peers(&self) -> RpcResult<Vec<String>>;
+
+        #[method(name = "set_limit")]
+        async fn set_limit(&self, limit: u64) -> RpcResult<()>;
+
+        #[method(name = "stop")]
+        async fn stop(&self) -> RpcResult<()>;
+    }
+}
+
+mod v2 {
+    use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+    use l2l_openapi::open_api;
+
+    #[open_api]
+    #[rpc(server)]
+    pub trait Node {
+        #[method(name = "get_block_hash")]
+        async fn get_block_hash(&self, height: u64, verbose: Option<bool>) -> RpcResult<String>;
+
+        #[method(name = "get_peers")]
+        async fn get_peers(&self) -> RpcResult<Vec<Option<String>>>;
+
+        #[method(name = "set_limit")]
+        async fn set_limit(&self, limit: u8) -> RpcResult<()>;
+
+        #[method(name = "shutdown")]
+        async fn shutdown(&self) -> RpcResult<()>;
+    }
+}
+
+#[test]
+fn test_diff() {
+    let diff = diff(&v1::NodeDoc::openapi(), &v2::NodeDoc::openapi());
+    let method = |method: &str| method.to_owned();
+    assert_eq!(
+        diff.0,
+        [
+            Change::MethodRemoved(method("stop")),
+            Change::MethodAdded(method("shutdown")),
+            Change::ParamChanged {
+                method: method("get_block_hash"),
+                param: "height".to_owned(),
+                compat: Compat::Wider,
+            },
+            Change::ParamAdded {
+                method: method("get_block_hash"),
+                param: "verbose".to_owned(),
+                required: false,
+            },
+            Change::ResultChanged {
+                method: method("get_peers"),
+                compat: Compat::Wider,
+            },
+            Change::ParamChanged {
+                method: method("set_limit"),
+                param: "limit".to_owned(),
+                compat: Compat::Narrower,
+            },
+        ]
+    );
+    assert!(diff.is_breaking());
+    let breaking: Vec<_> = diff
+        .0
+        .iter()
+        .filter(|change| change.is_breaking())
+        .map(ToString::to_string)
+        .collect();
+    assert_eq!(
+        breaking,
+        [
+            "removed method `stop`",
+            "`get_peers`: widened result type",
+            "`set_limit`: narrowed type of param `limit`",
+        ]
+    );
+}
+
+/// Document with a single method, `m`, with a param `n`
+fn param_doc(schema: Value) -> Value {
+    json!({
+        "paths": {
+            "m": {
+                "post": {
+                    "requestBody": { "content": { "application/json": { "schema": schema } } },
+                    "x-param-names": ["n"],
+                },
+            },
+        },
+    })
+}
+
+/// Exclusive bounds exclude the bound itself
+#[test]
+fn test_diff_exclusive_bounds() {
+    let param_compat = |old: Value, new: Value| {
+        let diff = diff_json(&param_doc(old), &param_doc(new));
+        match diff.0.as_slice() {
+            [] => Compat::Equal,
+            [Change::ParamChanged { compat, .. }] => *compat,
+            changes => panic!("unexpected changes: {changes:?}"),
+        }
+    };
+    let inclusive = json!({ "type": "number", "minimum": 0, "maximum": 10 });
+    let exclusive = json!({ "type": "number", "exclusiveMinimum": 0, "exclusiveMaximum": 10 });
+    assert_eq!(
+        param_compat(exclusive.clone(), inclusive.clone()),
+        Compat::Wider
+    );
+    assert_eq!(
+        param_compat(inclusive.clone(), exclusive.clone()),
+        Compat::Narrower
+    );
+    assert_eq!(
+        param_compat(exclusive.clone(), exclusive.clone()),
+        Compat::Equal
+    );
+    // OpenAPI 3.0 boolean exclusive bounds
+    let exclusive_bool = json!({
+        "type": "number",
+        "minimum": 0,
+        "exclusiveMinimum": true,
+        "maximum": 10,
+        "exclusiveMaximum": true,
+    });
+    assert_eq!(param_compat(exclusive_bool, inclusive), Compat::Wider);
+    // A higher exclusive minimum is narrower than a lower inclusive one
+    assert_eq!(
+        param_compat(json!({ "minimum": 5 }), json!({ "exclusiveMinimum": 5 })),
+        Compat::Narrower
+    );
+}
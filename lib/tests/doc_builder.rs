@@ -0,0 +1,62 @@
+#![cfg(feature = "gen-doc")]
+
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use l2l_openapi::{merge_all, open_api, DocBuilder, ParamNamesError};
+use serde::Serialize;
+use utoipa::{OpenApi, ToSchema};
+
+#[derive(Clone, Serialize, ToSchema)]
+pub struct Block {
+    pub height: u64,
+    pub hash: String,
+}
+
+#[open_api(ref_schemas [Block])]
+#[rpc(server)]
+pub trait Api {
+    /// Get a block
+    #[method(name = "get_block")]
+    async fn get_block(&self, height: u64, hash: String) -> RpcResult<Block>;
+}
+
+#[test]
+fn test_doc_builder() -> anyhow::Result<()> {
+    let mut doc_builder = DocBuilder::new();
+    doc_builder
+        .method::<(u64, String), Block>("get_block")
+        .param_names(["height", "hash"])?
+        .description("Get a block");
+    doc_builder.schema::<Block>();
+    doc_builder.method::<(), u64>("get_height");
+    let mut api = doc_builder.build();
+    // Same shape as the generated doc
+    assert!(api.paths.paths.get("get_block") == ApiDoc::openapi().paths.paths.get("get_block"));
+    assert!(api.components == ApiDoc::openapi().components);
+
+    api.paths.paths.remove("get_block");
+    let merged = merge_all([ApiDoc::openapi(), api])?;
+    assert_eq!(merged.paths.paths.len(), 2);
+    Ok(())
+}
+
+#[test]
+fn test_doc_builder_param_names_mismatch() {
+    let mut doc_builder = DocBuilder::new();
+    let Err(err) = doc_builder
+        .method::<(u64, String), Block>("get_block")
+        .param_names(["height"])
+    else {
+        panic!("expected param names error")
+    };
+    assert_eq!(
+        err,
+        ParamNamesError {
+            params: 2,
+            param_names: vec!["height".to_owned()]
+        }
+    );
+    assert_eq!(
+        err.to_string(),
+        r#"expected a name for each of 2 params, found ["height"]"#
+    );
+}
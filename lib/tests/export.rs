@@ -0,0 +1,118 @@
+#![cfg(feature = "gen-doc")]
+
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use l2l_openapi::{export, open_api, openrpc, ExportError};
+use serde_json::json;
+use utoipa::OpenApi;
+
+#[open_api]
+#[rpc(server)]
+pub trait Chain {
+    /// Get a block hash by height
+    #[open_api_method(example(params = "[0]", result = "\"00\""))]
+    #[method(name = "get_block_hash")]
+    async fn get_block_hash(&self, height: u32) -> RpcResult<String>;
+
+    #[method(name = "get_headers", param_kind = map)]
+    async fn get_headers(&self, start: u32, count: Option<u32>) -> RpcResult<Vec<String>>;
+}
+
+#[open_api]
+#[rpc(server)]
+pub trait Net {
+    #[method(name = "peer_count")]
+    async fn peer_count(&self) -> RpcResult<u32>;
+}
+
+mod v2 {
+    use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+    use l2l_openapi::open_api;
+
+    #[open_api]
+    #[rpc(server)]
+    pub trait Chain {
+        #[method(name = "get_tip")]
+        async fn get_tip(&self) -> RpcResult<String>;
+    }
+}
+
+#[test]
+fn test_openrpc() {
+    let doc = openrpc(&ChainDoc::openapi());
+    assert_eq!(doc["openrpc"], l2l_openapi::OPENRPC_VERSION);
+    let methods = doc["methods"].as_array().unwrap();
+    let get_block_hash = &methods[0];
+    assert_eq!(get_block_hash["name"], "get_block_hash");
+    assert_eq!(get_block_hash["description"], "Get a block hash by height");
+    assert_eq!(get_block_hash["params"][0]["name"], "height");
+    assert_eq!(get_block_hash["params"][0]["required"], true);
+    assert_eq!(
+        get_block_hash["result"]["schema"],
+        json!({ "type": "string" })
+    );
+    assert_eq!(
+        get_block_hash["examples"],
+        json!([{
+            "name": "0",
+            "params": [{ "name": "height", "value": 0 }],
+            "result": { "name": "result", "value": "00" },
+        }])
+    );
+    assert!(get_block_hash.get("paramStructure").is_none());
+    let get_headers = &methods[1];
+    assert_eq!(get_headers["paramStructure"], "by-name");
+    let params: Vec<_> = get_headers["params"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|param| (param["name"].clone(), param["required"].clone()))
+        .collect();
+    assert_eq!(
+        params,
+        [
+            (json!("start"), json!(true)),
+            (json!("count"), json!(false)),
+        ]
+    );
+}
+
+#[cfg(feature = "yaml")]
+#[test]
+fn test_export() -> anyhow::Result<()> {
+    let dir = std::env::temp_dir().join(format!("l2l-openapi-export-{}", std::process::id()));
+    let paths = export::<(ChainDoc, NetDoc)>(&dir)?;
+    let file_names: Vec<_> = paths
+        .iter()
+        .map(|path| path.strip_prefix(&dir).unwrap().to_str().unwrap())
+        .collect();
+    assert_eq!(
+        file_names,
+        [
+            "ChainDoc.json",
+            "ChainDoc.yaml",
+            "ChainDoc.openrpc.json",
+            "ChainDoc.ts",
+            "NetDoc.json",
+            "NetDoc.yaml",
+            "NetDoc.openrpc.json",
+            "NetDoc.ts",
+        ]
+    );
+    let json = std::fs::read_to_string(dir.join("NetDoc.json"))?;
+    assert_eq!(json, l2l_openapi::canonical_json(&NetDoc::openapi()));
+    let yaml = std::fs::read_to_string(dir.join("NetDoc.yaml"))?;
+    assert!(yaml.contains("peer_count:"));
+    std::fs::remove_dir_all(dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_export_duplicate_name() {
+    let dir = std::env::temp_dir().join(format!("l2l-openapi-export-dup-{}", std::process::id()));
+    let res = export::<(ChainDoc, NetDoc, v2::ChainDoc)>(&dir);
+    assert!(matches!(
+        res,
+        Err(ExportError::DuplicateName { name: "ChainDoc" })
+    ));
+    assert!(!dir.exists());
+}
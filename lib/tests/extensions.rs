@@ -0,0 +1,44 @@
+#![cfg(feature = "gen-doc")]
+
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use l2l_openapi::open_api;
+use serde_json::json;
+use utoipa::OpenApi;
+
+#[open_api(
+    extension(name = "x-internal", value = false),
+    extension(name = "x-gateway", value = { "timeout": 1.5, "retries": [1, 2] })
+)]
+#[rpc(server)]
+pub trait Node {
+    #[open_api_method(
+        extension(name = "x-cost", value = 5),
+        extension(name = "x-rate-limit", value = "10/s"),
+        extension(name = "x-offset", value = -2),
+        extension(name = "x-owner", value = null)
+    )]
+    #[method(name = "get_block", aliases = ["getblock"])]
+    async fn get_block(&self, hash: String) -> RpcResult<String>;
+
+    #[method(name = "stop")]
+    async fn stop(&self) -> RpcResult<()>;
+}
+
+#[test]
+fn test_extensions() -> anyhow::Result<()> {
+    let doc = serde_json::to_value(NodeDoc::openapi())?;
+    assert_eq!(doc["x-internal"], false);
+    assert_eq!(
+        doc["x-gateway"],
+        json!({ "timeout": 1.5, "retries": [1, 2] })
+    );
+    let get_block = &doc["paths"]["get_block"]["post"];
+    assert_eq!(get_block["x-aliases"], json!(["getblock"]));
+    assert_eq!(get_block["x-cost"], 5);
+    assert_eq!(get_block["x-rate-limit"], "10/s");
+    assert_eq!(get_block["x-offset"], -2);
+    assert_eq!(get_block.get("x-owner"), Some(&json!(null)));
+    let stop = doc["paths"]["stop"]["post"].as_object().unwrap();
+    assert!(!stop.keys().any(|key| key.starts_with("x-")));
+    Ok(())
+}
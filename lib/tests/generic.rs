@@ -0,0 +1,59 @@
+#![cfg(feature = "gen-doc")]
+
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use l2l_openapi::open_api;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::json;
+use utoipa::{OpenApi, PartialSchema, ToSchema};
+
+#[derive(Clone, Deserialize, Serialize, ToSchema)]
+pub struct BlockHash(pub [u8; 32]);
+
+#[derive(Clone, Deserialize, Serialize, ToSchema)]
+pub struct Header {
+    pub prev_block_hash: BlockHash,
+    pub height: u32,
+}
+
+#[open_api]
+#[rpc(
+    server,
+    server_bounds(
+        Hash: Clone + DeserializeOwned + Serialize + Send + Sync + 'static,
+        Header: Clone + DeserializeOwned + Serialize + Send + Sync + 'static
+    )
+)]
+pub trait Api<Hash, Header> {
+    /// Get a header by block hash
+    #[method(name = "header")]
+    async fn header(&self, block_hash: Hash) -> RpcResult<Option<Header>>;
+
+    /// Get headers by block hash
+    #[method(name = "headers")]
+    async fn headers(&self, block_hashes: Vec<Hash>) -> RpcResult<Vec<Header>>;
+}
+
+#[test]
+fn test_generic_openapi() -> anyhow::Result<()> {
+    let doc = serde_json::to_value(ApiDoc::<BlockHash, Header>::openapi())?;
+    let header = &doc["paths"]["header"]["post"];
+    // Schemas are for the type arguments
+    assert_eq!(
+        header["requestBody"]["content"]["application/json"]["schema"],
+        serde_json::to_value(BlockHash::schema())?
+    );
+    assert_eq!(
+        header["responses"]["200"]["content"]["application/json"]["schema"],
+        json!({ "oneOf": [{ "type": "null" }, Header::schema()] })
+    );
+    let headers = &doc["paths"]["headers"]["post"];
+    assert_eq!(
+        headers["requestBody"]["content"]["application/json"]["schema"],
+        json!({ "type": "array", "items": BlockHash::schema() })
+    );
+    assert_eq!(
+        headers["responses"]["200"]["content"]["application/json"]["schema"],
+        json!({ "type": "array", "items": Header::schema() })
+    );
+    Ok(())
+}
@@ -0,0 +1,62 @@
+#![cfg(feature = "gen-doc")]
+
+use jsonrpsee::{
+    core::{server::MethodsError, RpcResult},
+    proc_macros::rpc,
+    types::error::INVALID_PARAMS_CODE,
+};
+use l2l_openapi::open_api;
+
+#[open_api(help)]
+#[rpc(server)]
+pub trait Chain {
+    /// Get headers, starting at a height.
+    /// Returns at most `count` headers.
+    #[method(name = "get_headers", aliases = ["getheaders"])]
+    async fn get_headers(&self, start: u32, count: Option<u32>) -> RpcResult<Vec<String>>;
+
+    #[method(name = "stop")]
+    async fn stop(&self) -> RpcResult<()>;
+}
+
+#[test]
+fn test_help() {
+    assert_eq!(
+        ChainDoc::help(None),
+        Some("get_headers start ( count )\nstop\n")
+    );
+    let get_headers = "\
+get_headers start ( count )
+
+Get headers, starting at a height.
+Returns at most `count` headers.
+
+Aliases: getheaders
+
+Arguments:
+1. start    (u32, required)
+2. count    (Option<u32>, optional)
+
+Result:
+Vec<String>
+";
+    assert_eq!(ChainDoc::help(Some("get_headers")), Some(get_headers));
+    assert_eq!(ChainDoc::help(Some("getheaders")), Some(get_headers));
+    assert_eq!(ChainDoc::help(Some("unknown")), None);
+}
+
+#[tokio::test]
+async fn test_help_module() -> anyhow::Result<()> {
+    let module = ChainDoc::help_module();
+    let summary: String = module.call("help", [(); 0]).await?;
+    assert_eq!(summary, "get_headers start ( count )\nstop\n");
+    let stop: String = module.call("help", ["stop"]).await?;
+    assert_eq!(stop, "stop\n\nResult:\n()\n");
+    let Err(MethodsError::JsonRpc(err)) = module.call::<_, String>("help", ["unknown"]).await
+    else {
+        anyhow::bail!("expected unknown method error")
+    };
+    assert_eq!(err.code(), INVALID_PARAMS_CODE);
+    assert_eq!(err.message(), "unknown method `unknown`");
+    Ok(())
+}
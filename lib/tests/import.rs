@@ -0,0 +1,97 @@
+#![cfg(feature = "gen-doc")]
+
+use jsonrpsee::{http_client::HttpClient, server::Server};
+use utoipa::OpenApi;
+
+mod server {
+    use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+    use l2l_openapi::open_api;
+    use serde::Serialize;
+    use utoipa::ToSchema;
+
+    /// A block header
+    #[derive(Clone, Serialize, ToSchema)]
+    pub struct Header {
+        pub hash: String,
+        pub height: u32,
+        pub prev_hash: Option<String>,
+    }
+
+    #[open_api(ref_schemas [Header])]
+    #[rpc(server)]
+    pub trait Chain {
+        #[method(name = "get_headers", param_kind = map)]
+        async fn get_headers(&self, start: u32, count: Option<u32>) -> RpcResult<Vec<Header>>;
+
+        /// Get the current height
+        #[method(name = "height")]
+        async fn height(&self) -> RpcResult<u32>;
+
+        #[method(name = "getBlockHash")]
+        async fn get_block_hash(
+            &self,
+            #[argument(rename = "blockHeight")] height: u32,
+        ) -> RpcResult<String>;
+    }
+
+    pub struct ChainImpl;
+
+    #[jsonrpsee::core::async_trait]
+    impl ChainServer for ChainImpl {
+        async fn get_headers(&self, start: u32, count: Option<u32>) -> RpcResult<Vec<Header>> {
+            let headers = (start..start + count.unwrap_or(1))
+                .map(|height| Header {
+                    hash: format!("{height:02}"),
+                    height,
+                    prev_hash: height.checked_sub(1).map(|prev| format!("{prev:02}")),
+                })
+                .collect();
+            Ok(headers)
+        }
+
+        async fn height(&self) -> RpcResult<u32> {
+            Ok(7)
+        }
+
+        async fn get_block_hash(&self, height: u32) -> RpcResult<String> {
+            Ok(format!("{height:02}"))
+        }
+    }
+}
+
+mod client {
+    l2l_openapi::import!("tests/specs/chain.openrpc.json", trait_name = "ChainApi");
+}
+
+/// The imported document must be regenerated if the server trait changes
+#[test]
+fn test_import_spec_up_to_date() -> anyhow::Result<()> {
+    let spec = std::fs::read_to_string(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/specs/chain.openrpc.json"
+    ))?;
+    let spec: serde_json::Value = serde_json::from_str(&spec)?;
+    assert_eq!(spec, l2l_openapi::openrpc(&server::ChainDoc::openapi()));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_import() -> anyhow::Result<()> {
+    use client::ChainApiClient;
+    use server::ChainServer;
+    let server = Server::builder().build("127.0.0.1:0").await?;
+    let client = HttpClient::builder().build(format!("http://{}", server.local_addr()?))?;
+    let handle = server.start(server::ChainImpl.into_rpc());
+    assert_eq!(client.height().await?, 7);
+    let headers: Vec<client::Header> = client.get_headers(0, Some(2)).await?;
+    assert_eq!(headers.len(), 2);
+    assert_eq!(headers[0].prev_hash, None);
+    assert_eq!(headers[1].hash, "01");
+    assert_eq!(headers[1].prev_hash.as_deref(), Some("00"));
+    let headers = client.get_headers(5, None).await?;
+    assert_eq!(headers[0].height, 5);
+    // camelCase method and param names are kept
+    assert_eq!(client.getBlockHash(3).await?, "03");
+    handle.stop()?;
+    Ok(())
+}
@@ -0,0 +1,104 @@
+#![cfg(feature = "gen-doc")]
+
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use l2l_openapi::{merge, open_api, MergeConflict};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+mod a {
+    use super::*;
+
+    #[derive(Clone, Serialize, ToSchema)]
+    pub struct Shared {
+        pub value: u64,
+    }
+
+    #[derive(Clone, Serialize, ToSchema)]
+    #[schema(as = Shared)]
+    pub struct Conflicting {
+        pub value: String,
+    }
+
+    #[open_api(ref_schemas [Shared])]
+    #[rpc(server)]
+    pub trait A {
+        #[method(name = "a")]
+        async fn a(&self) -> RpcResult<u64>;
+    }
+
+    #[open_api(ref_schemas [Conflicting])]
+    #[rpc(server)]
+    pub trait Conflict {
+        #[method(name = "a")]
+        async fn a(&self) -> RpcResult<u64>;
+    }
+}
+
+#[open_api(ref_schemas [a::Shared])]
+#[rpc(server)]
+pub trait B {
+    #[method(name = "b")]
+    async fn b(&self) -> RpcResult<u64>;
+}
+
+mod ext {
+    use super::*;
+
+    #[open_api(
+        extension(name = "x-internal", value = false),
+        extension(name = "x-gateway", value = "edge")
+    )]
+    #[rpc(server)]
+    pub trait C {
+        #[method(name = "c")]
+        async fn c(&self) -> RpcResult<u64>;
+    }
+
+    #[open_api(extension(name = "x-internal", value = false))]
+    #[rpc(server)]
+    pub trait D {
+        #[method(name = "d")]
+        async fn d(&self) -> RpcResult<u64>;
+    }
+
+    #[open_api(extension(name = "x-internal", value = true))]
+    #[rpc(server)]
+    pub trait E {
+        #[method(name = "e")]
+        async fn e(&self) -> RpcResult<u64>;
+    }
+}
+
+#[test]
+fn test_merge() -> anyhow::Result<()> {
+    let api = merge::<(a::ADoc, BDoc)>()?;
+    assert_eq!(api.paths.paths.len(), 2);
+    assert_eq!(api.components.unwrap().schemas.len(), 1);
+    let Err(err) = merge::<(a::ADoc, BDoc, a::ConflictDoc)>() else {
+        anyhow::bail!("expected merge conflicts")
+    };
+    assert_eq!(
+        err.0,
+        vec![
+            MergeConflict::DuplicateOperationId("a".to_owned()),
+            MergeConflict::ConflictingSchema("Shared".to_owned()),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_merge_extensions() -> anyhow::Result<()> {
+    // Extensions from later documents are kept
+    let api = serde_json::to_value(merge::<(BDoc, ext::CDoc, ext::DDoc)>()?)?;
+    assert_eq!(api["x-internal"], false);
+    assert_eq!(api["x-gateway"], "edge");
+    let Err(err) = merge::<(ext::CDoc, ext::EDoc)>() else {
+        anyhow::bail!("expected merge conflicts")
+    };
+    assert_eq!(
+        err.0,
+        vec![MergeConflict::ConflictingExtension("x-internal".to_owned())]
+    );
+    Ok(())
+}
@@ -0,0 +1,85 @@
+#![cfg(feature = "gen-doc")]
+
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use l2l_openapi::{open_api, MethodFlags, MethodInfo, ParamInfo};
+use utoipa::{openapi::Deprecated, OpenApi};
+
+#[open_api]
+#[rpc(server, namespace = "chain")]
+pub trait Chain {
+    /// Get headers, starting at a height
+    #[open_api_method(tags = ["headers"], read_only)]
+    #[method(name = "get_headers", aliases = ["chain_getheaders"])]
+    async fn get_headers(&self, start: u32, count: Option<u32>) -> RpcResult<Vec<String>>;
+
+    #[open_api_method(deprecated, audience = "admin", mutating, idempotent)]
+    #[method(name = "rescan", blocking, param_kind = map)]
+    fn rescan(&self, #[argument(rename = "fromHeight")] from_height: u32) -> RpcResult<()>;
+}
+
+#[test]
+fn test_metadata() {
+    assert_eq!(
+        ChainDoc::METHODS,
+        [
+            MethodInfo {
+                name: "chain_get_headers",
+                aliases: &["chain_getheaders"],
+                params: &[
+                    ParamInfo {
+                        name: "start",
+                        ty: "u32",
+                    },
+                    ParamInfo {
+                        name: "count",
+                        ty: "Option<u32>",
+                    },
+                ],
+                result_ty: Some("Vec<String>"),
+                description: Some("Get headers, starting at a height"),
+                tags: &["headers"],
+                deprecated: false,
+                audience: "public",
+                flags: MethodFlags {
+                    read_only: true,
+                    ..MethodFlags::default()
+                },
+            },
+            MethodInfo {
+                name: "chain_rescan",
+                aliases: &[],
+                params: &[ParamInfo {
+                    name: "fromHeight",
+                    ty: "u32",
+                }],
+                result_ty: Some("()"),
+                description: None,
+                tags: &[],
+                deprecated: true,
+                audience: "admin",
+                flags: MethodFlags {
+                    blocking: true,
+                    named_params: true,
+                    read_only: false,
+                    idempotent: true,
+                    mutating: true,
+                },
+            },
+        ]
+    );
+    let method = MethodInfo::find(ChainDoc::METHODS, "chain_getheaders").unwrap();
+    assert_eq!(method.name, "chain_get_headers");
+    assert!(MethodInfo::find(ChainDoc::METHODS, "get_headers").is_none());
+}
+
+#[test]
+fn test_tags_and_deprecated() {
+    let openapi = ChainDoc::openapi();
+    let operation = |path: &str| openapi.paths.paths[path].post.clone().unwrap();
+    assert_eq!(
+        operation("chain_get_headers").tags,
+        Some(vec!["headers".to_owned()])
+    );
+    assert!(operation("chain_get_headers").deprecated.is_none());
+    assert!(operation("chain_rescan").deprecated == Some(Deprecated::True));
+}
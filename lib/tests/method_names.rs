@@ -0,0 +1,44 @@
+#![cfg(feature = "gen-doc")]
+
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use l2l_openapi::open_api;
+use utoipa::OpenApi;
+
+#[open_api]
+#[rpc(server, namespace = "chain", namespace_separator = ".")]
+pub trait Chain {
+    #[method(name = "getHeight", aliases = ["getblockcount"])]
+    async fn get_height(&self) -> RpcResult<u32>;
+}
+
+#[open_api]
+#[rpc(server, namespace = "node")]
+pub trait Node {
+    #[method(name = "peerCount")]
+    async fn peer_count(&self) -> RpcResult<u32>;
+}
+
+/// Paths and operation IDs are wire method names, including the namespace
+#[test]
+fn test_method_names() -> anyhow::Result<()> {
+    let openapi = ChainDoc::openapi();
+    assert!(!openapi.paths.paths.contains_key("get_height"));
+    let Some(path_item) = openapi.paths.paths.get("chain.getHeight") else {
+        anyhow::bail!("missing path `chain.getHeight`")
+    };
+    let Some(operation) = &path_item.post else {
+        anyhow::bail!("missing operation")
+    };
+    assert_eq!(operation.operation_id.as_deref(), Some("chain.getHeight"));
+    let aliases = operation
+        .extensions
+        .as_ref()
+        .and_then(|extensions| extensions.get("x-aliases"));
+    assert_eq!(aliases, Some(&vec!["getblockcount"].into()));
+
+    // The default namespace separator is `_`
+    let openapi = NodeDoc::openapi();
+    let paths: Vec<&str> = openapi.paths.paths.keys().map(String::as_str).collect();
+    assert_eq!(paths, ["node_peerCount"]);
+    Ok(())
+}
@@ -0,0 +1,73 @@
+#![cfg(all(feature = "gen-doc", feature = "middleware"))]
+
+use jsonrpsee::{
+    core::{client::ClientT, params::BatchRequestBuilder, RpcResult},
+    http_client::HttpClient,
+    proc_macros::rpc,
+    rpc_params,
+    server::{middleware::rpc::RpcServiceBuilder, Server, ServerHandle},
+};
+use l2l_openapi::{open_api, ValidateParamsLayer};
+use serde_json::{json, Value};
+use utoipa::OpenApi;
+
+#[open_api]
+#[rpc(server)]
+pub trait Wallet {
+    #[method(name = "send", aliases = ["transfer"])]
+    async fn send(&self, #[argument(rename = "to")] address: String, amount: u8) -> RpcResult<u8>;
+}
+
+struct WalletImpl;
+
+#[jsonrpsee::core::async_trait]
+impl WalletServer for WalletImpl {
+    async fn send(&self, _address: String, amount: u8) -> RpcResult<u8> {
+        Ok(amount)
+    }
+}
+
+async fn client() -> anyhow::Result<(HttpClient, ServerHandle)> {
+    let rpc_middleware =
+        RpcServiceBuilder::new().layer(ValidateParamsLayer::new(&WalletDoc::openapi()));
+    let server = Server::builder()
+        .set_rpc_middleware(rpc_middleware)
+        .build("127.0.0.1:0")
+        .await?;
+    let url = format!("http://{}", server.local_addr()?);
+    let handle = server.start(WalletImpl.into_rpc());
+    Ok((HttpClient::builder().build(url)?, handle))
+}
+
+#[tokio::test]
+async fn test_validate_params() -> anyhow::Result<()> {
+    let (client, handle) = client().await?;
+    let amount: u8 = client.request("send", rpc_params!["addr", 1]).await?;
+    assert_eq!(amount, 1);
+
+    let jsonrpsee::core::ClientError::Call(err) = client
+        .request::<u8, _>("transfer", rpc_params!["addr", -1])
+        .await
+        .unwrap_err()
+    else {
+        anyhow::bail!("expected call error")
+    };
+    assert_eq!(err.code(), -32602);
+    let data: Value = serde_json::from_str(err.data().unwrap().get())?;
+    assert_eq!(
+        data,
+        json!([{
+            "instancePath": "/1",
+            "schemaPath": "/paths/send/post/requestBody/content/application~1json/schema/properties/amount/minimum",
+            "message": "-1 is less than 0",
+        }])
+    );
+
+    let mut batch = BatchRequestBuilder::new();
+    batch.insert("send", rpc_params!["addr", 2])?;
+    batch.insert("send", rpc_params![0, 2])?;
+    let responses = client.batch_request::<u8>(batch).await?;
+    assert_eq!(responses.num_successful_calls(), 1);
+    handle.stop()?;
+    Ok(())
+}
@@ -3,9 +3,11 @@ use std::net::SocketAddr;
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 use l2l_openapi::open_api;
 use serde::Serialize;
+use utoipa::ToSchema;
+#[cfg(feature = "gen-doc")]
 use utoipa::{
     openapi::{self, RefOr, Schema},
-    PartialSchema, ToSchema,
+    PartialSchema,
 };
 
 #[derive(Clone, Serialize, ToSchema)]
@@ -26,8 +28,10 @@ pub struct InnerRefs {
     pub inner1: Inner1,
 }
 
+#[cfg(feature = "gen-doc")]
 struct SocketAddrSchema;
 
+#[cfg(feature = "gen-doc")]
 impl PartialSchema for SocketAddrSchema {
     fn schema() -> RefOr<Schema> {
         let obj = utoipa::openapi::Object::with_type(openapi::Type::String);
@@ -35,7 +39,7 @@ impl PartialSchema for SocketAddrSchema {
     }
 }
 
-#[open_api(ref_schemas [Inner0, Inner1])]
+#[open_api(ref_schemas [Inner0, Inner1], validate_test)]
 #[rpc(server)]
 pub trait TestRpc {
     /// Doc comment
@@ -92,6 +96,22 @@ pub trait TestRpc {
     async fn subscribe(&self) -> jsonrpsee::core::SubscriptionResult;
 }
 
+mod renamed {
+    use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+
+    #[l2l_openapi::open_api(
+        doc_struct = "RenamedDoc",
+        doc_vis = "pub(crate)",
+        crate = "::l2l_openapi"
+    )]
+    #[rpc(server)]
+    pub trait Renamed {
+        #[method(name = "renamed")]
+        async fn renamed(&self, some_u32: u32) -> RpcResult<u32>;
+    }
+}
+
+#[cfg(feature = "gen-doc")]
 #[test]
 fn test_print_openapi() -> anyhow::Result<()> {
     use utoipa::OpenApi;
@@ -99,3 +119,10 @@ fn test_print_openapi() -> anyhow::Result<()> {
     println!("{api_str}");
     Ok(())
 }
+
+#[cfg(feature = "gen-doc")]
+#[test]
+fn test_doc_struct_options() {
+    use utoipa::OpenApi;
+    assert_eq!(renamed::RenamedDoc::openapi().paths.paths.len(), 1);
+}
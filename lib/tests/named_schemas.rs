@@ -0,0 +1,140 @@
+#![cfg(all(feature = "gen-doc", feature = "middleware"))]
+
+use jsonrpsee::{
+    core::{client::ClientT, RpcResult},
+    http_client::HttpClient,
+    proc_macros::rpc,
+    rpc_params,
+    server::{middleware::rpc::RpcServiceBuilder, Server},
+};
+use l2l_openapi::{open_api, openrpc, typescript, ValidateParamsLayer};
+use serde_json::{json, Value};
+use utoipa::OpenApi;
+
+#[open_api(named_schemas)]
+#[rpc(server)]
+pub trait Chain {
+    #[method(name = "get_block_hash")]
+    async fn get_block_hash(&self, height: u32) -> RpcResult<String>;
+
+    #[method(name = "get_headers")]
+    async fn get_headers(
+        &self,
+        start: u32,
+        #[argument(rename = "maxCount")] max_count: Option<u32>,
+    ) -> RpcResult<Vec<String>>;
+}
+
+struct ChainImpl;
+
+#[jsonrpsee::core::async_trait]
+impl ChainServer for ChainImpl {
+    async fn get_block_hash(&self, height: u32) -> RpcResult<String> {
+        Ok(height.to_string())
+    }
+
+    async fn get_headers(&self, start: u32, max_count: Option<u32>) -> RpcResult<Vec<String>> {
+        let count = max_count.unwrap_or(1);
+        Ok((start..start + count)
+            .map(|height| height.to_string())
+            .collect())
+    }
+}
+
+#[test]
+fn test_named_schemas() -> anyhow::Result<()> {
+    let doc = serde_json::to_value(ChainDoc::openapi())?;
+    let schemas = &doc["components"]["schemas"];
+    assert_eq!(
+        schemas["GetHeadersParams"]["properties"],
+        json!({
+            "start": { "type": "integer", "format": "int32", "minimum": 0 },
+            "maxCount": {
+                "oneOf": [
+                    { "type": "null" },
+                    { "type": "integer", "format": "int32", "minimum": 0 },
+                ],
+            },
+        })
+    );
+    assert_eq!(
+        schemas["GetHeadersResult"],
+        json!({ "type": "array", "items": { "type": "string" } })
+    );
+    assert_eq!(schemas["GetBlockHashResult"], json!({ "type": "string" }));
+    // Single params are not wrapped
+    assert!(schemas.get("GetBlockHashParams").is_none());
+    let get_headers = &doc["paths"]["get_headers"]["post"];
+    assert_eq!(
+        get_headers["requestBody"]["content"]["application/json"]["schema"],
+        json!({ "$ref": "#/components/schemas/GetHeadersParams" })
+    );
+    assert_eq!(
+        get_headers["responses"]["200"]["content"]["application/json"]["schema"],
+        json!({ "$ref": "#/components/schemas/GetHeadersResult" })
+    );
+
+    let params: GetHeadersParams = serde_json::from_value(json!({ "start": 3 }))?;
+    assert_eq!((params.start, params.max_count), (3, None));
+    assert_eq!(
+        serde_json::to_value(GetHeadersParams {
+            start: 3,
+            max_count: Some(2),
+        })?,
+        json!({ "start": 3, "maxCount": 2 })
+    );
+    let _: GetHeadersResult = vec!["00".to_owned()];
+    Ok(())
+}
+
+/// Param schemas are resolved through the params component
+#[test]
+fn test_named_schemas_params() -> anyhow::Result<()> {
+    let doc = openrpc(&ChainDoc::openapi());
+    let get_headers = &doc["methods"][1];
+    assert_eq!(get_headers["params"][1]["name"], "maxCount");
+    assert_eq!(get_headers["params"][1]["required"], false);
+    assert_eq!(
+        get_headers["result"]["schema"],
+        json!({ "$ref": "#/components/schemas/GetHeadersResult" })
+    );
+
+    let ts = typescript(&ChainDoc::openapi())?;
+    assert!(
+        ts.contains("export type GetHeadersParams = [start: number, maxCount?: null | number];")
+    );
+    assert!(ts.contains("export type GetHeadersResult = string[];"));
+    assert_eq!(ts.matches("GetHeadersResult =").count(), 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_named_schemas_middleware() -> anyhow::Result<()> {
+    let rpc_middleware =
+        RpcServiceBuilder::new().layer(ValidateParamsLayer::new(&ChainDoc::openapi()));
+    let server = Server::builder()
+        .set_rpc_middleware(rpc_middleware)
+        .build("127.0.0.1:0")
+        .await?;
+    let url = format!("http://{}", server.local_addr()?);
+    let handle = server.start(ChainImpl.into_rpc());
+    let client = HttpClient::builder().build(url)?;
+    let headers: Vec<String> = client.request("get_headers", rpc_params![3, 2]).await?;
+    assert_eq!(headers, ["3", "4"]);
+
+    let jsonrpsee::core::ClientError::Call(err) = client
+        .request::<Vec<String>, _>("get_headers", rpc_params![3, "2"])
+        .await
+        .unwrap_err()
+    else {
+        anyhow::bail!("expected call error")
+    };
+    assert_eq!(err.code(), -32602);
+    let data: Value = serde_json::from_str(err.data().unwrap().get())?;
+    assert_eq!(
+        data[0]["schemaPath"],
+        "/components/schemas/GetHeadersParams/properties/maxCount/oneOf"
+    );
+    handle.stop()?;
+    Ok(())
+}
@@ -0,0 +1,207 @@
+#![cfg(all(feature = "client", feature = "gen-doc", feature = "middleware"))]
+
+use std::{
+    sync::atomic::{AtomicU32, Ordering},
+    time::Duration,
+};
+
+use jsonrpsee::{
+    core::{client::ClientT, RpcResult},
+    http_client::HttpClient,
+    proc_macros::rpc,
+    rpc_params,
+    server::{middleware::rpc::RpcServiceBuilder, Server, ServerHandle},
+};
+use l2l_openapi::{open_api, ReplicaRouterLayer, ResponseCacheLayer};
+use utoipa::OpenApi;
+
+#[open_api]
+#[rpc(server)]
+pub trait Node {
+    #[open_api_method(read_only)]
+    #[method(name = "get_calls", aliases = ["getcalls"])]
+    async fn get_calls(&self) -> RpcResult<String>;
+
+    #[open_api_method(mutating, idempotent)]
+    #[method(name = "set_calls")]
+    async fn set_calls(&self, calls: u32) -> RpcResult<String>;
+}
+
+/// Counts calls, and returns the count with the node name
+struct NodeImpl {
+    name: &'static str,
+    calls: AtomicU32,
+}
+
+impl NodeImpl {
+    fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            calls: AtomicU32::new(0),
+        }
+    }
+
+    fn call(&self) -> String {
+        let calls = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+        format!("{} {calls}", self.name)
+    }
+}
+
+#[jsonrpsee::core::async_trait]
+impl NodeServer for NodeImpl {
+    async fn get_calls(&self) -> RpcResult<String> {
+        Ok(self.call())
+    }
+
+    async fn set_calls(&self, calls: u32) -> RpcResult<String> {
+        self.calls.store(calls, Ordering::SeqCst);
+        Ok(self.call())
+    }
+}
+
+/// Start a server without middleware
+async fn server(name: &'static str) -> anyhow::Result<(String, ServerHandle)> {
+    let server = Server::builder().build("127.0.0.1:0").await?;
+    let url = format!("http://{}", server.local_addr()?);
+    Ok((url, server.start(NodeServer::into_rpc(NodeImpl::new(name)))))
+}
+
+#[test]
+fn test_side_effect_extensions() -> anyhow::Result<()> {
+    let doc = serde_json::to_value(NodeDoc::openapi())?;
+    let get_calls = &doc["paths"]["get_calls"]["post"];
+    assert_eq!(get_calls["x-read-only"], true);
+    assert!(get_calls.get("x-mutating").is_none());
+    let set_calls = &doc["paths"]["set_calls"]["post"];
+    assert_eq!(set_calls["x-mutating"], true);
+    assert_eq!(set_calls["x-idempotent"], true);
+    assert!(set_calls.get("x-read-only").is_none());
+    let read_only: Vec<_> = NodeDoc::METHODS
+        .iter()
+        .filter(|method| method.flags.read_only)
+        .map(|method| method.name)
+        .collect();
+    assert_eq!(read_only, ["get_calls"]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_response_cache() -> anyhow::Result<()> {
+    let rpc_middleware = RpcServiceBuilder::new().layer(ResponseCacheLayer::new(
+        &NodeDoc::openapi(),
+        Duration::from_millis(200),
+        16,
+    ));
+    let server = Server::builder()
+        .set_rpc_middleware(rpc_middleware)
+        .build("127.0.0.1:0")
+        .await?;
+    let url = format!("http://{}", server.local_addr()?);
+    let handle = server.start(NodeServer::into_rpc(NodeImpl::new("node")));
+    let client = HttpClient::builder().build(url)?;
+    for _ in 0..2 {
+        let res: String = client.request("get_calls", rpc_params![]).await?;
+        assert_eq!(res, "node 1");
+    }
+    // Aliases are cached separately
+    let res: String = client.request("getcalls", rpc_params![]).await?;
+    assert_eq!(res, "node 2");
+    // Mutating methods are not cached
+    for expected in ["node 6", "node 6"] {
+        let res: String = client.request("set_calls", rpc_params![5]).await?;
+        assert_eq!(res, expected);
+    }
+    tokio::time::sleep(Duration::from_millis(250)).await;
+    let res: String = client.request("get_calls", rpc_params![]).await?;
+    assert_eq!(res, "node 7");
+    handle.stop()?;
+    Ok(())
+}
+
+#[open_api(security_schemes(token = "bearer"))]
+#[rpc(server)]
+pub trait Wallet {
+    #[open_api_method(read_only)]
+    #[method(name = "get_balance")]
+    async fn get_balance(&self) -> RpcResult<String>;
+
+    #[open_api_method(read_only, security = [])]
+    #[method(name = "get_label")]
+    async fn get_label(&self, idx: u32) -> RpcResult<String>;
+}
+
+#[jsonrpsee::core::async_trait]
+impl WalletServer for NodeImpl {
+    async fn get_balance(&self) -> RpcResult<String> {
+        Ok(self.call())
+    }
+
+    async fn get_label(&self, idx: u32) -> RpcResult<String> {
+        Ok(format!("{idx}: {}", self.call()))
+    }
+}
+
+/// Authenticated methods are not cached, and the oldest responses are
+/// evicted at capacity
+#[tokio::test]
+async fn test_response_cache_capacity() -> anyhow::Result<()> {
+    let rpc_middleware = RpcServiceBuilder::new().layer(ResponseCacheLayer::new(
+        &WalletDoc::openapi(),
+        Duration::from_secs(60),
+        2,
+    ));
+    let server = Server::builder()
+        .set_rpc_middleware(rpc_middleware)
+        .build("127.0.0.1:0")
+        .await?;
+    let url = format!("http://{}", server.local_addr()?);
+    let handle = server.start(WalletServer::into_rpc(NodeImpl::new("wallet")));
+    let client = HttpClient::builder().build(url)?;
+    for expected in ["wallet 1", "wallet 2"] {
+        let res: String = client.request("get_balance", rpc_params![]).await?;
+        assert_eq!(res, expected);
+    }
+    for (idx, expected) in [
+        (0, "0: wallet 3"),
+        (1, "1: wallet 4"),
+        (0, "0: wallet 3"),
+        // Evicts the response for 0
+        (2, "2: wallet 5"),
+        (1, "1: wallet 4"),
+        (0, "0: wallet 6"),
+    ] {
+        let res: String = client.request("get_label", rpc_params![idx]).await?;
+        assert_eq!(res, expected);
+    }
+    handle.stop()?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_replica_router() -> anyhow::Result<()> {
+    let (replica_url, replica_handle) = server("replica").await?;
+    let replica = HttpClient::builder().build(replica_url)?;
+    let rpc_middleware =
+        RpcServiceBuilder::new().layer(ReplicaRouterLayer::new(&NodeDoc::openapi(), replica));
+    let server = Server::builder()
+        .set_rpc_middleware(rpc_middleware)
+        .build("127.0.0.1:0")
+        .await?;
+    let url = format!("http://{}", server.local_addr()?);
+    let handle = server.start(NodeServer::into_rpc(NodeImpl::new("primary")));
+    let client = HttpClient::builder().build(url)?;
+    let res: String = client.request("get_calls", rpc_params![]).await?;
+    assert_eq!(res, "replica 1");
+    let res: String = client.request("getcalls", rpc_params![]).await?;
+    assert_eq!(res, "replica 2");
+    let res: String = client.request("set_calls", rpc_params![5]).await?;
+    assert_eq!(res, "primary 6");
+
+    // Calls fall back to the primary if the replica is unreachable
+    replica_handle.stop()?;
+    replica_handle.stopped().await;
+    let res: String = client.request("get_calls", rpc_params![]).await?;
+    assert_eq!(res, "primary 7");
+    handle.stop()?;
+    Ok(())
+}
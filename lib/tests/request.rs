@@ -0,0 +1,85 @@
+#![cfg(feature = "gen-doc")]
+
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use l2l_openapi::open_api;
+use serde_json::json;
+
+#[open_api(request_enums)]
+#[rpc(server)]
+pub trait Chain {
+    /// Get headers, starting at a height
+    #[method(name = "get_headers", aliases = ["getheaders"])]
+    async fn get_headers(&self, start: u32, count: Option<u32>) -> RpcResult<Vec<String>>;
+
+    #[method(name = "rescan", param_kind = map)]
+    async fn rescan(&self, #[argument(rename = "fromHeight")] from_height: u32) -> RpcResult<()>;
+
+    #[method(name = "stop")]
+    async fn stop(&self) -> RpcResult<bool>;
+}
+
+#[test]
+fn test_request_enum() -> anyhow::Result<()> {
+    let request: ChainRequest = serde_json::from_value(json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "getheaders",
+        "params": [3],
+    }))?;
+    assert!(matches!(
+        request,
+        ChainRequest::GetHeaders {
+            start: 3,
+            count: None
+        }
+    ));
+    assert_eq!(request.method(), "get_headers");
+    assert_eq!(
+        serde_json::to_value(&request)?,
+        json!({ "method": "get_headers", "params": [3, null] })
+    );
+
+    let request = ChainRequest::from_call("rescan", json!({ "fromHeight": 7 }))?;
+    assert!(matches!(request, ChainRequest::Rescan { from_height: 7 }));
+    assert_eq!(request.params()?, json!({ "fromHeight": 7 }));
+
+    let request = ChainRequest::from_call("stop", serde_json::Value::Null)?;
+    assert!(matches!(request, ChainRequest::Stop));
+    assert_eq!(request.params()?, json!([]));
+
+    let err_msg = |method: &str, params| {
+        ChainRequest::from_call(method, params)
+            .err()
+            .map(|err| err.to_string())
+    };
+    assert_eq!(
+        err_msg("unknown", json!([])).as_deref(),
+        Some("unknown method `unknown`")
+    );
+    assert_eq!(
+        err_msg("rescan", json!([7])).as_deref(),
+        Some("expected an object of params for `rescan`")
+    );
+    assert_eq!(
+        err_msg("stop", json!([true])).as_deref(),
+        Some("too many params for `stop`")
+    );
+    assert_eq!(
+        err_msg("get_headers", json!([])).as_deref(),
+        Some("invalid param `start`: invalid type: null, expected u32")
+    );
+    Ok(())
+}
+
+#[test]
+fn test_response_enum() -> anyhow::Result<()> {
+    let response = ChainResponse::from_result("getheaders", json!(["00", "01"]))?;
+    assert_eq!(response.method(), "get_headers");
+    let ChainResponse::GetHeaders(headers) = &response else {
+        anyhow::bail!("expected get_headers response")
+    };
+    assert_eq!(headers, &["00", "01"]);
+    assert_eq!(serde_json::to_value(&response)?, json!(["00", "01"]));
+    assert!(ChainResponse::from_result("stop", json!("yes")).is_err());
+    Ok(())
+}
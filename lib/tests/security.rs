@@ -0,0 +1,60 @@
+#![cfg(feature = "gen-doc")]
+
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use l2l_openapi::{merge_all, open_api, MergeConflict};
+use serde_json::json;
+use utoipa::OpenApi;
+
+#[open_api(security_schemes(token = "bearer", password = "basic"))]
+#[rpc(server)]
+pub trait Node {
+    #[open_api_method(security = [])]
+    #[method(name = "get_block_count")]
+    async fn get_block_count(&self) -> RpcResult<u32>;
+
+    #[open_api_method(security = ["token"])]
+    #[method(name = "stop")]
+    async fn stop(&self) -> RpcResult<()>;
+
+    #[method(name = "get_peer_info")]
+    async fn get_peer_info(&self) -> RpcResult<Vec<String>>;
+}
+
+#[open_api(security_schemes(token = "basic"))]
+#[rpc(server)]
+pub trait Wallet {
+    #[method(name = "get_balance")]
+    async fn get_balance(&self) -> RpcResult<u64>;
+}
+
+#[test]
+fn test_security() -> anyhow::Result<()> {
+    let doc = serde_json::to_value(NodeDoc::openapi())?;
+    assert_eq!(
+        doc["components"]["securitySchemes"],
+        json!({
+            "password": { "type": "http", "scheme": "basic" },
+            "token": { "type": "http", "scheme": "bearer" },
+        })
+    );
+    let paths = &doc["paths"];
+    assert_eq!(paths["get_block_count"]["post"]["security"], json!([]));
+    assert_eq!(paths["stop"]["post"]["security"], json!([{ "token": [] }]));
+    assert_eq!(
+        paths["get_peer_info"]["post"]["security"],
+        json!([{ "token": [] }, { "password": [] }])
+    );
+    assert!(doc.get("security").is_none());
+    Ok(())
+}
+
+#[test]
+fn test_merge_conflicting_security_schemes() {
+    let Err(err) = merge_all([NodeDoc::openapi(), WalletDoc::openapi()]) else {
+        panic!("expected merge conflict")
+    };
+    assert_eq!(
+        err.0,
+        [MergeConflict::ConflictingSecurityScheme("token".to_owned())]
+    );
+}
@@ -0,0 +1,38 @@
+#![cfg(feature = "gen-doc")]
+
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use l2l_openapi::{check_snapshot, open_api, SnapshotError};
+use utoipa::OpenApi;
+
+#[open_api(snapshot = "tests/snapshots/wallet.json")]
+#[rpc(server)]
+pub trait Wallet {
+    /// Get the balance of the wallet
+    #[method(name = "balance")]
+    async fn balance(&self) -> RpcResult<u64>;
+
+    /// Send to an address
+    #[method(name = "send")]
+    async fn send(&self, address: String, amount: u64) -> RpcResult<String>;
+}
+
+#[test]
+fn test_snapshot_mismatch() -> anyhow::Result<()> {
+    // Snapshots are written instead of checked
+    if std::env::var(l2l_openapi::BLESS_ENV_VAR).is_ok_and(|bless| bless == "1") {
+        return Ok(());
+    }
+    let path =
+        std::env::temp_dir().join(format!("l2l-openapi-snapshot-{}.json", std::process::id()));
+    let mut openapi = WalletDoc::openapi();
+    openapi.paths.paths.remove("send");
+    std::fs::write(&path, l2l_openapi::canonical_json(&openapi))?;
+    check_snapshot(&openapi, &path)?;
+    let res = check_snapshot(&WalletDoc::openapi(), &path);
+    std::fs::remove_file(&path)?;
+    let Err(SnapshotError::Mismatch { pointer, .. }) = res else {
+        anyhow::bail!("expected mismatch")
+    };
+    assert_eq!(pointer, "/paths/send");
+    Ok(())
+}
@@ -0,0 +1,244 @@
+#![cfg(all(feature = "client", feature = "gen-doc"))]
+
+use std::net::SocketAddr;
+
+use jsonrpsee::{
+    core::RpcResult,
+    http_client::HttpClient,
+    proc_macros::rpc,
+    server::{Server, ServerHandle},
+};
+use l2l_openapi::{
+    open_api,
+    testing::{check_conformance, fuzz, ConformanceIssue, FuzzConfig, FuzzIssue, FuzzReport},
+};
+use utoipa::{
+    openapi::{self, ObjectBuilder, RefOr, Schema},
+    OpenApi, PartialSchema,
+};
+
+/// Incorrect schema for `SocketAddr`
+struct SocketAddrSchema;
+
+impl PartialSchema for SocketAddrSchema {
+    fn schema() -> RefOr<Schema> {
+        let obj = openapi::Object::with_type(openapi::Type::Integer);
+        RefOr::T(Schema::Object(obj))
+    }
+}
+
+#[open_api]
+#[rpc(server)]
+pub trait Peers {
+    #[method(name = "peer_count")]
+    async fn peer_count(&self) -> RpcResult<u32>;
+
+    #[open_api_method(
+        output_schema(PartialSchema = "SocketAddrSchema"),
+        example(params = "[0]", result = "\"127.0.0.1:8000\""),
+        example(params = "[1]")
+    )]
+    #[method(name = "peer_addr")]
+    async fn peer_addr(&self, idx: u32) -> RpcResult<SocketAddr>;
+
+    #[open_api_method(example(params = r#"[0, "reason"]"#), example(params = "[0]"))]
+    #[method(name = "ban_peer")]
+    async fn ban_peer(&self, idx: u32, reason: Option<String>) -> RpcResult<()>;
+
+    #[open_api_method(example(params = r#"[0, "seed"]"#, result = "\"seed\""))]
+    #[method(name = "label_peer", param_kind = map)]
+    async fn label_peer(&self, idx: u32, label: String) -> RpcResult<String>;
+}
+
+struct PeersImpl;
+
+#[jsonrpsee::core::async_trait]
+impl PeersServer for PeersImpl {
+    async fn peer_count(&self) -> RpcResult<u32> {
+        Ok(2)
+    }
+
+    async fn peer_addr(&self, idx: u32) -> RpcResult<SocketAddr> {
+        Ok(SocketAddr::from(([127, 0, 0, 1], 8000 + idx as u16)))
+    }
+
+    async fn ban_peer(&self, _idx: u32, _reason: Option<String>) -> RpcResult<()> {
+        Ok(())
+    }
+
+    async fn label_peer(&self, _idx: u32, label: String) -> RpcResult<String> {
+        Ok(label)
+    }
+}
+
+async fn client() -> anyhow::Result<(HttpClient, ServerHandle)> {
+    let server = Server::builder().build("127.0.0.1:0").await?;
+    let url = format!("http://{}", server.local_addr()?);
+    let handle = server.start(PeersImpl.into_rpc());
+    Ok((HttpClient::builder().build(url)?, handle))
+}
+
+#[tokio::test]
+async fn test_check_conformance() -> anyhow::Result<()> {
+    let doc = serde_json::to_value(PeersDoc::openapi())?;
+    assert_eq!(
+        doc["paths"]["label_peer"]["post"]["x-param-structure"],
+        "by-name"
+    );
+    assert!(doc["paths"]["ban_peer"]["post"]
+        .get("x-param-structure")
+        .is_none());
+    let (client, handle) = client().await?;
+    let Err(err) = check_conformance(&client, &PeersDoc::openapi()).await else {
+        anyhow::bail!("expected conformance error")
+    };
+    let methods: Vec<_> = err
+        .0
+        .iter()
+        .map(|issue| match issue {
+            ConformanceIssue::InvalidResult { method, params, .. } => {
+                (method.as_str(), params.clone())
+            }
+            _ => panic!("unexpected issue: {issue}"),
+        })
+        .collect();
+    assert_eq!(
+        methods,
+        [("peer_addr", vec![0.into()]), ("peer_addr", vec![1.into()])]
+    );
+    handle.stop()?;
+    Ok(())
+}
+
+#[open_api]
+#[rpc(server)]
+pub trait Node {
+    #[method(name = "block_hash")]
+    async fn block_hash(&self, height: u32, verbose: Option<bool>) -> RpcResult<String>;
+
+    #[method(name = "connect_peer")]
+    async fn connect_peer(
+        &self,
+        #[open_api_method_arg(schema(PartialSchema = "SocketAddrSchema"))] addr: SocketAddr,
+    ) -> RpcResult<()>;
+
+    #[method(name = "set_label")]
+    async fn set_label(
+        &self,
+        #[open_api_method_arg(schema(PartialSchema = "String"))] label: serde_json::Value,
+    ) -> RpcResult<()>;
+
+    #[method(name = "resolve_host")]
+    async fn resolve_host(
+        &self,
+        #[open_api_method_arg(schema(PartialSchema = "HostnameSchema"))] host: String,
+    ) -> RpcResult<()>;
+}
+
+struct NodeImpl;
+
+#[jsonrpsee::core::async_trait]
+impl NodeServer for NodeImpl {
+    async fn block_hash(&self, height: u32, _verbose: Option<bool>) -> RpcResult<String> {
+        Ok(height.to_string())
+    }
+
+    async fn connect_peer(&self, _addr: SocketAddr) -> RpcResult<()> {
+        Ok(())
+    }
+
+    async fn set_label(&self, _label: serde_json::Value) -> RpcResult<()> {
+        Ok(())
+    }
+
+    async fn resolve_host(&self, _host: String) -> RpcResult<()> {
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_fuzz() -> anyhow::Result<()> {
+    let server = Server::builder().build("127.0.0.1:0").await?;
+    let client = HttpClient::builder().build(format!("http://{}", server.local_addr()?))?;
+    let handle = server.start(NodeImpl.into_rpc());
+    let Err(err) = fuzz(&client, &NodeDoc::openapi(), &FuzzConfig::default()).await else {
+        anyhow::bail!("expected fuzz error")
+    };
+    let issues: Vec<_> = err
+        .issues
+        .iter()
+        .map(|issue| match issue {
+            FuzzIssue::ValidRejected { method, .. } => ("valid rejected", method.as_str()),
+            FuzzIssue::InvalidAccepted { method, .. } => ("invalid accepted", method.as_str()),
+            FuzzIssue::RequestFailed { .. } => panic!("unexpected issue: {issue}"),
+        })
+        .collect();
+    assert_eq!(
+        issues,
+        [
+            ("valid rejected", "connect_peer"),
+            ("invalid accepted", "set_label")
+        ]
+    );
+    // Skipped methods are reported with issues
+    assert_eq!(err.report.skipped, ["resolve_host"]);
+    handle.stop()?;
+    Ok(())
+}
+
+/// Schema for hostnames, that values cannot be generated for
+struct HostnameSchema;
+
+impl PartialSchema for HostnameSchema {
+    fn schema() -> RefOr<Schema> {
+        let obj = ObjectBuilder::new()
+            .schema_type(openapi::Type::String)
+            .pattern(Some("^[a-z0-9.-]+$"))
+            .build();
+        RefOr::T(Schema::Object(obj))
+    }
+}
+
+#[open_api]
+#[rpc(server)]
+pub trait Hosts {
+    #[method(name = "add_host", param_kind = map)]
+    async fn add_host(&self, port: u32, label: Option<String>) -> RpcResult<()>;
+
+    #[method(name = "resolve_host")]
+    async fn resolve_host(
+        &self,
+        #[open_api_method_arg(schema(PartialSchema = "HostnameSchema"))] host: String,
+    ) -> RpcResult<()>;
+}
+
+struct HostsImpl;
+
+#[jsonrpsee::core::async_trait]
+impl HostsServer for HostsImpl {
+    async fn add_host(&self, _port: u32, _label: Option<String>) -> RpcResult<()> {
+        Ok(())
+    }
+
+    async fn resolve_host(&self, _host: String) -> RpcResult<()> {
+        Ok(())
+    }
+}
+
+/// By-name methods are fuzzed with object params, and methods that params
+/// cannot be generated for are reported
+#[tokio::test]
+async fn test_fuzz_report() -> anyhow::Result<()> {
+    let server = Server::builder().build("127.0.0.1:0").await?;
+    let client = HttpClient::builder().build(format!("http://{}", server.local_addr()?))?;
+    let handle = server.start(HostsImpl.into_rpc());
+    let report = fuzz(&client, &HostsDoc::openapi(), &FuzzConfig::default()).await?;
+    assert_eq!(
+        report,
+        FuzzReport {
+            skipped: vec!["resolve_host".to_owned()]
+        }
+    );
+    handle.stop()?;
+    Ok(())
+}
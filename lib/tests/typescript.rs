@@ -0,0 +1,116 @@
+#![cfg(feature = "gen-doc")]
+
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use l2l_openapi::{open_api, typescript, DocBuilder, TypeScriptError};
+use serde::Serialize;
+use utoipa::{OpenApi, PartialSchema, ToSchema};
+
+/// A block header
+#[derive(Clone, Serialize, ToSchema)]
+pub struct Header {
+    pub hash: String,
+    pub height: u32,
+    /// Hash of the previous block, if any
+    pub prev_hash: Option<String>,
+}
+
+#[open_api(ref_schemas [Header])]
+#[rpc(server)]
+pub trait Chain {
+    /// Get a header by hash
+    #[open_api_method(output_schema(ToSchema))]
+    #[method(name = "get_header", aliases = ["getheader"])]
+    async fn get_header(&self, hash: String) -> RpcResult<Option<Header>>;
+
+    #[method(name = "get_hashes")]
+    async fn get_hashes(&self, start: u32, count: Option<u32>) -> RpcResult<Vec<String>>;
+
+    #[method(name = "stop")]
+    async fn stop(&self) -> RpcResult<bool>;
+}
+
+#[test]
+fn test_typescript() -> anyhow::Result<()> {
+    let ts = typescript(&ChainDoc::openapi())?;
+    let expected_decls = [
+        "/** A block header */
+export interface Header {
+  hash: string;
+  height: number;
+  /** Hash of the previous block, if any */
+  prev_hash?: string | null;
+}
+",
+        "export type GetHashesParams = [start: number, count?: null | number];
+export type GetHashesResult = string[];
+",
+        "/** Get a header by hash */
+export type GetHeaderParams = [hash: string];
+",
+        "export type StopParams = [];
+export type StopResult = boolean;
+",
+        "export interface Methods {
+  get_hashes: { params: GetHashesParams; result: GetHashesResult };
+  get_header: { params: GetHeaderParams; result: GetHeaderResult };
+  getheader: { params: GetHeaderParams; result: GetHeaderResult };
+  stop: { params: StopParams; result: StopResult };
+}
+",
+        "export type Call = <M extends MethodName>(",
+    ];
+    for decl in expected_decls {
+        assert!(ts.contains(decl), "missing declaration:\n{decl}\nin:\n{ts}");
+    }
+    Ok(())
+}
+
+/// Params are not dropped if `x-param-names` is invalid
+#[test]
+fn test_typescript_invalid_param_names() {
+    let mut openapi = ChainDoc::openapi();
+    let get_header = openapi.paths.paths.get_mut("get_header").unwrap();
+    let extensions = get_header
+        .post
+        .as_mut()
+        .unwrap()
+        .extensions
+        .as_mut()
+        .unwrap();
+    extensions.insert("x-param-names".to_owned(), serde_json::json!([0]));
+    let ts = typescript(&openapi).unwrap();
+    assert!(ts.contains("export type GetHeaderParams = [params: string];"));
+}
+
+/// Components are only replaced by params types if they are the params
+#[test]
+fn test_typescript_component_collision() {
+    let mut openapi = ChainDoc::openapi();
+    let components = openapi.components.get_or_insert_with(Default::default);
+    components
+        .schemas
+        .insert("GetHashesParams".to_owned(), String::schema());
+    assert_eq!(
+        typescript(&openapi),
+        Err(TypeScriptError {
+            ident: "GetHashesParams".to_owned(),
+            sources: vec![
+                "method `get_hashes`".to_owned(),
+                "component `GetHashesParams`".to_owned()
+            ],
+        })
+    );
+}
+
+/// Method names that map to the same identifier are an error
+#[test]
+fn test_typescript_method_collision() {
+    let mut doc_builder = DocBuilder::new();
+    doc_builder.method::<(u32,), String>("get_block");
+    doc_builder.method::<(u32,), String>("get.block");
+    let Err(err) = typescript(&doc_builder.build()) else {
+        panic!("expected TypeScript error")
+    };
+    assert_eq!(err.ident, "GetBlockParams");
+    assert_eq!(err.sources, ["method `get.block`", "method `get_block`"]);
+}
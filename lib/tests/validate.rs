@@ -0,0 +1,49 @@
+use l2l_openapi::{validate, DocBuilder, ValidationIssue};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+#[derive(Clone, Serialize, ToSchema)]
+pub struct Inner {
+    pub inner_bool: bool,
+}
+
+#[derive(Clone, Serialize, ToSchema)]
+pub struct Outer {
+    pub inner: Inner,
+}
+
+#[derive(Clone, Serialize, ToSchema)]
+pub struct Unused {
+    pub unused_u64: u64,
+}
+
+#[test]
+fn test_validate() -> anyhow::Result<()> {
+    let mut doc_builder = DocBuilder::new();
+    doc_builder.method::<(u64,), Outer>("outer");
+    doc_builder.method::<(serde_json::Value,), u64>("empty_param");
+    doc_builder.schema::<Unused>();
+    let openapi = doc_builder.build();
+    let Err(err) = validate(&openapi) else {
+        anyhow::bail!("expected validation issues")
+    };
+    assert_eq!(
+        err.0,
+        vec![
+            ValidationIssue::DanglingRef {
+                location: "/paths/outer/post/responses/200/content/application~1json/schema/properties/inner".to_owned(),
+                reference: "#/components/schemas/Inner".to_owned(),
+            },
+            ValidationIssue::UnusedComponent("Unused".to_owned()),
+            ValidationIssue::EmptySchema {
+                location: "/paths/empty_param/post/requestBody/content/application~1json/schema".to_owned(),
+            },
+        ]
+    );
+
+    let mut doc_builder = DocBuilder::new();
+    doc_builder.method::<(u64,), Outer>("outer");
+    doc_builder.schema::<Inner>();
+    validate(&doc_builder.build())?;
+    Ok(())
+}
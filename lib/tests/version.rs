@@ -0,0 +1,91 @@
+#![cfg(feature = "gen-doc")]
+
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use l2l_openapi::{open_api, VersionError};
+use utoipa::OpenApi;
+
+#[open_api]
+#[rpc(server)]
+pub trait Node {
+    #[method(name = "get_block_count")]
+    async fn get_block_count(&self) -> RpcResult<u32>;
+
+    /// Get the best block hash
+    #[open_api_method(since = "0.5.0")]
+    #[method(name = "get_best_block_hash")]
+    async fn get_best_block_hash(&self) -> RpcResult<String>;
+
+    #[open_api_method(since = "0.5", removed_in = "0.8.0")]
+    #[method(name = "get_chain_tip")]
+    async fn get_chain_tip(&self) -> RpcResult<String>;
+
+    #[open_api_method(removed_in = "0.6.1")]
+    #[method(name = "get_info")]
+    async fn get_info(&self) -> RpcResult<String>;
+}
+
+fn method_names(openapi: &utoipa::openapi::OpenApi) -> Vec<&str> {
+    openapi.paths.paths.keys().map(String::as_str).collect()
+}
+
+#[test]
+fn test_version_extensions() -> anyhow::Result<()> {
+    let doc = serde_json::to_value(NodeDoc::openapi())?;
+    let get_best_block_hash = &doc["paths"]["get_best_block_hash"]["post"];
+    assert_eq!(get_best_block_hash["x-since"], "0.5.0");
+    assert!(get_best_block_hash.get("x-removed-in").is_none());
+    assert_eq!(
+        get_best_block_hash["description"],
+        "Get the best block hash\n\nAvailable since version 0.5.0."
+    );
+    let get_chain_tip = &doc["paths"]["get_chain_tip"]["post"];
+    assert_eq!(get_chain_tip["x-removed-in"], "0.8.0");
+    assert_eq!(
+        get_chain_tip["description"],
+        "Available since version 0.5. Removed in version 0.8.0."
+    );
+    assert!(doc["paths"]["get_block_count"]["post"]
+        .get("description")
+        .is_none());
+    Ok(())
+}
+
+#[test]
+fn test_openapi_at_version() -> anyhow::Result<()> {
+    assert_eq!(
+        method_names(&NodeDoc::openapi_at_version("0.4.9")?),
+        ["get_block_count", "get_info"]
+    );
+    assert_eq!(
+        method_names(&NodeDoc::openapi_at_version("0.5")?),
+        [
+            "get_best_block_hash",
+            "get_block_count",
+            "get_chain_tip",
+            "get_info"
+        ]
+    );
+    assert_eq!(
+        method_names(&NodeDoc::openapi_at_version("0.6.1")?),
+        ["get_best_block_hash", "get_block_count", "get_chain_tip"]
+    );
+    assert_eq!(
+        method_names(&NodeDoc::openapi_at_version("0.10.0")?),
+        ["get_best_block_hash", "get_block_count"]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_openapi_at_invalid_version() {
+    let Err(err) = NodeDoc::openapi_at_version("latest") else {
+        panic!("expected version error")
+    };
+    assert_eq!(
+        err,
+        VersionError {
+            version: "latest".to_owned()
+        }
+    );
+    assert_eq!(err.to_string(), "invalid version: `latest`");
+}
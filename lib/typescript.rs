@@ -0,0 +1,442 @@
+//! Generate TypeScript types from documents
+
+use std::fmt::Write as _;
+
+use serde_json::{Map, Value};
+use utoipa::openapi::OpenApi;
+
+use crate::{
+    json_schema::Validator,
+    util::{json_extension_strs, json_operations, json_param_names, param_schema_paths},
+};
+
+/// Returns true if the string is a valid TypeScript identifier
+fn is_ident(s: &str) -> bool {
+    let mut chars = s.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+}
+
+/// TypeScript identifier for a component schema name
+fn type_ident(name: &str) -> String {
+    let ident: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if ident.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{ident}")
+    } else {
+        ident
+    }
+}
+
+/// PascalCase TypeScript identifier for a method name
+fn method_ident(name: &str) -> String {
+    let ident: String = name
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .flat_map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|c| c.to_ascii_uppercase())
+                .into_iter()
+                .chain(chars)
+        })
+        .collect();
+    type_ident(&ident)
+}
+
+/// Property key, quoted if it is not an identifier
+fn property_key(name: &str) -> String {
+    if is_ident(name) {
+        name.to_owned()
+    } else {
+        Value::String(name.to_owned()).to_string()
+    }
+}
+
+/// JSDoc comment for a description, at the given indent
+fn doc_comment(description: Option<&Value>, indent: &str) -> String {
+    let Some(description) = description.and_then(Value::as_str) else {
+        return String::new();
+    };
+    let description = description.replace("*/", "*\\/");
+    let mut lines = description.lines();
+    match (lines.next(), lines.next()) {
+        (None, _) => String::new(),
+        (Some(line), None) => format!("{indent}/** {line} */\n"),
+        (Some(_), Some(_)) => {
+            let mut res = format!("{indent}/**\n");
+            for line in description.lines() {
+                let line = format!("{indent} * {line}");
+                let _ = writeln!(res, "{}", line.trim_end());
+            }
+            let _ = writeln!(res, "{indent} */");
+            res
+        }
+    }
+}
+
+/// Join types, parenthesizing members that contain unions or intersections
+fn join_tys(tys: Vec<String>, sep: &str) -> String {
+    let mut tys: Vec<String> = tys
+        .into_iter()
+        .map(|ty| {
+            if tys_need_parens(&ty) {
+                format!("({ty})")
+            } else {
+                ty
+            }
+        })
+        .collect();
+    tys.dedup();
+    tys.join(sep)
+}
+
+/// Returns true if a type must be parenthesized in a union, intersection or
+/// array
+fn tys_need_parens(ty: &str) -> bool {
+    let mut depth = 0usize;
+    ty.chars().any(|c| {
+        match c {
+            '{' | '[' | '(' | '<' => depth += 1,
+            '}' | ']' | ')' | '>' => depth = depth.saturating_sub(1),
+            '|' | '&' if depth == 0 => return true,
+            _ => (),
+        }
+        false
+    })
+}
+
+/// Inline object type for object properties
+fn object_ty(schema: &Map<String, Value>) -> String {
+    let required: Vec<&str> = match schema.get("required") {
+        Some(Value::Array(required)) => required.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    };
+    let mut members: Vec<String> = schema
+        .get("properties")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flatten()
+        .map(|(name, property)| {
+            let optional = if required.contains(&name.as_str()) {
+                ""
+            } else {
+                "?"
+            };
+            format!("{}{optional}: {}", property_key(name), ty(property))
+        })
+        .collect();
+    match schema.get("additionalProperties") {
+        Some(Value::Bool(false)) => (),
+        Some(additional) => members.push(format!("[key: string]: {}", ty(additional))),
+        None if members.is_empty() => members.push("[key: string]: unknown".to_owned()),
+        None => (),
+    }
+    format!("{{ {} }}", members.join("; "))
+}
+
+/// TypeScript type for a JSON type name
+fn json_ty(json_ty: &str, schema: &Map<String, Value>) -> String {
+    match json_ty {
+        "null" => "null".to_owned(),
+        "boolean" => "boolean".to_owned(),
+        "integer" | "number" => "number".to_owned(),
+        "string" => "string".to_owned(),
+        "array" => match (schema.get("prefixItems"), schema.get("items")) {
+            (Some(Value::Array(prefix_items)), _) => {
+                let tys: Vec<String> = prefix_items.iter().map(ty).collect();
+                format!("[{}]", tys.join(", "))
+            }
+            (_, Some(items)) => {
+                let item_ty = ty(items);
+                if tys_need_parens(&item_ty) {
+                    format!("({item_ty})[]")
+                } else {
+                    format!("{item_ty}[]")
+                }
+            }
+            (_, None) => "unknown[]".to_owned(),
+        },
+        "object" => object_ty(schema),
+        _ => "unknown".to_owned(),
+    }
+}
+
+/// TypeScript type for a schema
+fn ty(schema: &Value) -> String {
+    let schema = match schema {
+        Value::Bool(true) => return "unknown".to_owned(),
+        Value::Object(schema) => schema,
+        _ => return "never".to_owned(),
+    };
+    if let Some(Value::String(reference)) = schema.get("$ref") {
+        return match reference.strip_prefix("#/components/schemas/") {
+            Some(name) => type_ident(name),
+            None => "unknown".to_owned(),
+        };
+    }
+    if let Some(value) = schema.get("const") {
+        return value.to_string();
+    }
+    if let Some(Value::Array(values)) = schema.get("enum") {
+        return join_tys(values.iter().map(Value::to_string).collect(), " | ");
+    }
+    for (keyword, sep) in [("oneOf", " | "), ("anyOf", " | "), ("allOf", " & ")] {
+        if let Some(Value::Array(schemas)) = schema.get(keyword) {
+            return join_tys(schemas.iter().map(ty).collect(), sep);
+        }
+    }
+    match schema.get("type") {
+        Some(Value::String(ty)) => json_ty(ty, schema),
+        Some(Value::Array(tys)) => join_tys(
+            tys.iter()
+                .filter_map(Value::as_str)
+                .map(|ty| json_ty(ty, schema))
+                .collect(),
+            " | ",
+        ),
+        _ if schema.contains_key("properties") => object_ty(schema),
+        _ => "unknown".to_owned(),
+    }
+}
+
+/// Declaration for a component schema
+fn component_decl(name: &str, schema: &Value) -> String {
+    let mut res = doc_comment(schema.get("description"), "");
+    let ident = type_ident(name);
+    let is_object = schema.get("type") == Some(&Value::String("object".to_owned()));
+    match schema.as_object() {
+        Some(object) if is_object && object.contains_key("properties") => {
+            let required: Vec<&str> = match object.get("required") {
+                Some(Value::Array(required)) => required.iter().filter_map(Value::as_str).collect(),
+                _ => Vec::new(),
+            };
+            let _ = writeln!(res, "export interface {ident} {{");
+            for (name, property) in object["properties"].as_object().into_iter().flatten() {
+                res.push_str(&doc_comment(property.get("description"), "  "));
+                let optional = if required.contains(&name.as_str()) {
+                    ""
+                } else {
+                    "?"
+                };
+                let _ = writeln!(res, "  {}{optional}: {};", property_key(name), ty(property));
+            }
+            res.push_str("}\n");
+        }
+        _ => {
+            let _ = writeln!(res, "export type {ident} = {};", ty(schema));
+        }
+    }
+    res
+}
+
+/// Error for a TypeScript identifier that would be declared more than once
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TypeScriptError {
+    pub ident: String,
+    /// What the identifier was declared for, eg. ``component `GetBlock` ``
+    /// or ``method `get_block` ``
+    pub sources: Vec<String>,
+}
+
+impl std::fmt::Display for TypeScriptError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "`{}` would be declared for each of: {}",
+            self.ident,
+            self.sources.join(", ")
+        )
+    }
+}
+
+impl std::error::Error for TypeScriptError {}
+
+/// A method in the generated `Methods` map
+struct Method {
+    name: String,
+    aliases: Vec<String>,
+    description: Option<Value>,
+    params: String,
+    /// Component that the params schema references, eg. from
+    /// `#[open_api(named_schemas)]`
+    params_component: Option<String>,
+    result: String,
+}
+
+impl Method {
+    fn new(doc: &Value, path: &str, operation: &Value) -> Self {
+        let validator = Validator::new(doc);
+        let param_names = json_param_names(operation);
+        // Each param name has a schema path, so every param is named
+        let mut params: Vec<(&str, String, bool)> = param_names
+            .iter()
+            .zip(param_schema_paths(doc, path, &param_names))
+            .map(|(name, schema_path)| {
+                let schema = doc.pointer(&schema_path).unwrap_or(&Value::Bool(true));
+                let nullable = validator.is_valid(schema, &schema_path, &Value::Null);
+                (*name, ty(schema), nullable)
+            })
+            .collect();
+        // Only trailing params that accept null may be omitted
+        let required_len = params
+            .iter()
+            .rposition(|(_, _, nullable)| !nullable)
+            .map_or(0, |idx| idx + 1);
+        params[..required_len]
+            .iter_mut()
+            .for_each(|(_, _, nullable)| *nullable = false);
+        let params: Vec<String> = params
+            .into_iter()
+            .map(|(name, ty, optional)| {
+                let optional = if optional { "?" } else { "" };
+                let name = type_ident(name);
+                format!("{name}{optional}: {ty}")
+            })
+            .collect();
+        let params_component = operation
+            .pointer("/requestBody/content/application~1json/schema/$ref")
+            .and_then(Value::as_str)
+            .and_then(|reference| reference.strip_prefix("#/components/schemas/"))
+            .map(str::to_owned);
+        let result = operation
+            .pointer("/responses/200/content/application~1json/schema")
+            .map_or_else(|| "void".to_owned(), ty);
+        let aliases = json_extension_strs(operation, "x-aliases")
+            .unwrap_or_default()
+            .into_iter()
+            .map(str::to_owned)
+            .collect();
+        Self {
+            name: path.to_owned(),
+            aliases,
+            description: operation.get("description").cloned(),
+            params: format!("[{}]", params.join(", ")),
+            params_component,
+            result,
+        }
+    }
+}
+
+const PRELUDE: &str = "\
+export type MethodName = keyof Methods;
+
+export type Params<M extends MethodName> = Methods[M][\"params\"];
+
+export type Result<M extends MethodName> = Methods[M][\"result\"];
+
+/** Call a method with typed params and result */
+export type Call = <M extends MethodName>(
+  method: M,
+  params: Params<M>,
+) => Promise<Result<M>>;
+";
+
+/// Generate TypeScript declarations for a document.
+///
+/// The output contains:
+/// * a type or interface for each component schema
+/// * `{Method}Params` and `{Method}Result` types for each method, where
+///   params are a labeled tuple in positional order
+/// * a `Methods` map from method names and aliases to their params and
+///   result types, along with `MethodName`, `Params<M>`, `Result<M>` and a
+///   `Call` signature
+///
+/// Params components, eg. from `#[open_api(named_schemas)]`, are replaced by
+/// the params tuple types.
+///
+/// # Errors
+/// Returns an error if an identifier would be declared more than once, eg.
+/// for methods `get_block` and `get.block`, or a component named
+/// `GetBlockParams` that is not the params of `get_block`.
+pub fn typescript(openapi: &OpenApi) -> Result<String, TypeScriptError> {
+    let doc = serde_json::to_value(openapi).expect("document should serialize");
+    let mut res = "// Generated by l2l-openapi. Do not edit.\n".to_owned();
+    let methods: Vec<Method> = json_operations(&doc)
+        .map(|(path, operation)| Method::new(&doc, path, operation))
+        .collect();
+    let schemas = match doc.pointer("/components/schemas") {
+        Some(Value::Object(schemas)) => Some(schemas),
+        _ => None,
+    };
+    // Declared identifiers, with what they are declared for
+    let mut decls: Vec<(String, String)> = ["Methods", "MethodName", "Params", "Result", "Call"]
+        .into_iter()
+        .map(|ident| (ident.to_owned(), "the `Methods` map".to_owned()))
+        .collect();
+    let mut skipped_components = Vec::new();
+    for method in &methods {
+        let ident = method_ident(&method.name);
+        let params_ident = format!("{ident}Params");
+        match &method.params_component {
+            Some(component) if type_ident(component) == params_ident => {
+                skipped_components.push(component.as_str());
+            }
+            _ => (),
+        }
+        decls.push((params_ident, format!("method `{}`", method.name)));
+        // Result components with the same name are declared as components
+        if method.result != format!("{ident}Result") {
+            decls.push((
+                format!("{ident}Result"),
+                format!("method `{}`", method.name),
+            ));
+        }
+    }
+    for name in schemas.into_iter().flat_map(|schemas| schemas.keys()) {
+        if !skipped_components.contains(&name.as_str()) {
+            decls.push((type_ident(name), format!("component `{name}`")));
+        }
+    }
+    for (idx, (ident, _)) in decls.iter().enumerate() {
+        if decls[..idx]
+            .iter()
+            .any(|(prev_ident, _)| prev_ident == ident)
+        {
+            let sources = decls
+                .iter()
+                .filter(|(decl_ident, _)| decl_ident == ident)
+                .map(|(_, source)| source.clone())
+                .collect();
+            return Err(TypeScriptError {
+                ident: ident.clone(),
+                sources,
+            });
+        }
+    }
+    for (name, schema) in schemas.into_iter().flatten() {
+        if skipped_components.contains(&name.as_str()) {
+            continue;
+        }
+        res.push('\n');
+        res.push_str(&component_decl(name, schema));
+    }
+    for method in &methods {
+        let ident = method_ident(&method.name);
+        res.push('\n');
+        res.push_str(&doc_comment(method.description.as_ref(), ""));
+        let _ = writeln!(res, "export type {ident}Params = {};", method.params);
+        // Result components with the same name are already declared
+        if method.result != format!("{ident}Result") {
+            let _ = writeln!(res, "export type {ident}Result = {};", method.result);
+        }
+    }
+    res.push_str("\nexport interface Methods {\n");
+    for method in &methods {
+        let ident = method_ident(&method.name);
+        for name in std::iter::once(&method.name).chain(&method.aliases) {
+            let _ = writeln!(
+                res,
+                "  {}: {{ params: {ident}Params; result: {ident}Result }};",
+                property_key(name)
+            );
+        }
+    }
+    res.push_str("}\n\n");
+    res.push_str(PRELUDE);
+    Ok(res)
+}
@@ -0,0 +1,133 @@
+use serde_json::Value;
+use utoipa::openapi::{path::Operation, OpenApi, PathItem};
+
+/// All operations of a path item
+pub(crate) fn path_item_operations(path_item: &PathItem) -> impl Iterator<Item = &Operation> {
+    let PathItem {
+        get,
+        put,
+        post,
+        delete,
+        options,
+        head,
+        patch,
+        trace,
+        ..
+    } = path_item;
+    [get, put, post, delete, options, head, patch, trace]
+        .into_iter()
+        .flatten()
+}
+
+/// All operations in a document, with their paths
+pub(crate) fn operations(openapi: &OpenApi) -> impl Iterator<Item = (&str, &Operation)> {
+    openapi.paths.paths.iter().flat_map(|(path, path_item)| {
+        path_item_operations(path_item).map(move |operation| (path.as_str(), operation))
+    })
+}
+
+/// Remove operations that do not satisfy a predicate, and path items that
+/// have no remaining operations
+pub(crate) fn retain_operations<F>(openapi: &mut OpenApi, mut f: F)
+where
+    F: FnMut(&Operation) -> bool,
+{
+    openapi.paths.paths.retain(|_, path_item| {
+        let PathItem {
+            get,
+            put,
+            post,
+            delete,
+            options,
+            head,
+            patch,
+            trace,
+            ..
+        } = path_item;
+        let mut retained = false;
+        for operation in [get, put, post, delete, options, head, patch, trace] {
+            if operation.as_ref().is_some_and(|operation| !f(operation)) {
+                *operation = None;
+            }
+            retained |= operation.is_some();
+        }
+        retained
+    });
+}
+
+/// Escape a JSON pointer segment
+pub(crate) fn escape_pointer_segment(segment: &str) -> String {
+    segment.replace('~', "~0").replace('/', "~1")
+}
+
+/// String values of an operation extension
+#[cfg(any(feature = "client", feature = "middleware", feature = "server"))]
+pub(crate) fn extension_strs<'a>(operation: &'a Operation, name: &str) -> Option<Vec<&'a str>> {
+    operation
+        .extensions
+        .as_ref()?
+        .get(name)?
+        .as_array()?
+        .iter()
+        .map(Value::as_str)
+        .collect()
+}
+
+/// Operations in a JSON document, with their paths
+pub(crate) fn json_operations(doc: &Value) -> impl Iterator<Item = (&str, &Value)> {
+    doc.get("paths")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flatten()
+        .filter_map(|(path, path_item)| Some((path.as_str(), path_item.get("post")?)))
+}
+
+/// String values of an extension of a JSON operation
+pub(crate) fn json_extension_strs<'a>(operation: &'a Value, name: &str) -> Option<Vec<&'a str>> {
+    operation
+        .get(name)?
+        .as_array()?
+        .iter()
+        .map(Value::as_str)
+        .collect()
+}
+
+/// Param names of a JSON operation, from the `x-param-names` extension.
+/// Operations with a request body, but without valid param names, have a
+/// single param, `params`.
+pub(crate) fn json_param_names(operation: &Value) -> Vec<&str> {
+    match json_extension_strs(operation, "x-param-names") {
+        Some(names) if !names.is_empty() => names,
+        _ if operation.get("requestBody").is_some() => vec!["params"],
+        _ => Vec::new(),
+    }
+}
+
+/// JSON pointers to the schemas for each param of the method at a path.
+/// A single param uses the request body schema. Multiple params are
+/// properties of the request body schema, or of the component schema that
+/// it references.
+pub(crate) fn param_schema_paths(doc: &Value, path: &str, param_names: &[&str]) -> Vec<String> {
+    let request_body_schema_path = format!(
+        "/paths/{}/post/requestBody/content/application~1json/schema",
+        escape_pointer_segment(path)
+    );
+    if let [_] = param_names {
+        return vec![request_body_schema_path];
+    }
+    let object_schema_path = match doc
+        .pointer(&format!("{request_body_schema_path}/$ref"))
+        .and_then(Value::as_str)
+        .and_then(|reference| reference.strip_prefix('#'))
+    {
+        Some(reference) => reference.to_owned(),
+        None => request_body_schema_path,
+    };
+    param_names
+        .iter()
+        .map(|name| {
+            let name = escape_pointer_segment(name);
+            format!("{object_schema_path}/properties/{name}")
+        })
+        .collect()
+}
@@ -0,0 +1,234 @@
+//! Validate generated documents
+
+use std::collections::{BTreeSet, HashSet};
+
+use serde_json::Value;
+use utoipa::openapi::OpenApi;
+
+use crate::util::{escape_pointer_segment, operations};
+
+/// Issue found when validating a document.
+/// Locations are JSON pointers into the document.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ValidationIssue {
+    /// A `$ref` that does not resolve
+    DanglingRef { location: String, reference: String },
+    /// A component schema that is not reachable from any path
+    UnusedComponent(String),
+    /// More than one operation uses the same operation ID
+    DuplicateOperationId(String),
+    /// A schema with no keywords
+    EmptySchema { location: String },
+}
+
+impl std::fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::DanglingRef {
+                location,
+                reference,
+            } => write!(f, "dangling reference `{reference}` at `{location}`"),
+            Self::UnusedComponent(name) => write!(f, "unused component schema `{name}`"),
+            Self::DuplicateOperationId(operation_id) => {
+                write!(f, "duplicate operation ID: `{operation_id}`")
+            }
+            Self::EmptySchema { location } => write!(f, "empty schema at `{location}`"),
+        }
+    }
+}
+
+/// Error when validating a document. Contains all issues that were found.
+#[derive(Clone, Debug)]
+pub struct ValidationError(pub Vec<ValidationIssue>);
+
+impl std::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid document")?;
+        self.0.iter().try_for_each(|issue| write!(f, "\n  {issue}"))
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// Schemas and references found when walking a document
+#[derive(Default)]
+struct Walker {
+    /// Locations and values of `$ref`s
+    refs: Vec<(String, String)>,
+    /// Locations of empty schemas
+    empty_schemas: Vec<String>,
+}
+
+impl Walker {
+    /// Walk a schema and its subschemas
+    fn walk_schema(&mut self, location: &str, schema: &Value) {
+        let Value::Object(schema) = schema else {
+            return;
+        };
+        if schema.is_empty() {
+            self.empty_schemas.push(location.to_owned());
+            return;
+        }
+        if let Some(Value::String(reference)) = schema.get("$ref") {
+            self.refs.push((location.to_owned(), reference.clone()));
+        }
+        for keyword in [
+            "items",
+            "additionalProperties",
+            "not",
+            "contains",
+            "propertyNames",
+        ] {
+            if let Some(subschema) = schema.get(keyword) {
+                self.walk_schema(&format!("{location}/{keyword}"), subschema);
+            }
+        }
+        for keyword in ["allOf", "anyOf", "oneOf", "prefixItems"] {
+            if let Some(Value::Array(subschemas)) = schema.get(keyword) {
+                for (idx, subschema) in subschemas.iter().enumerate() {
+                    self.walk_schema(&format!("{location}/{keyword}/{idx}"), subschema);
+                }
+            }
+        }
+        for keyword in ["properties", "patternProperties", "$defs"] {
+            if let Some(Value::Object(subschemas)) = schema.get(keyword) {
+                for (name, subschema) in subschemas {
+                    let name = escape_pointer_segment(name);
+                    self.walk_schema(&format!("{location}/{keyword}/{name}"), subschema);
+                }
+            }
+        }
+    }
+
+    /// Walk schemas in a `content` map
+    fn walk_content(&mut self, location: &str, content: Option<&Value>) {
+        let Some(Value::Object(content)) = content else {
+            return;
+        };
+        for (media_type, media_type_obj) in content {
+            if let Some(schema) = media_type_obj.get("schema") {
+                let media_type = escape_pointer_segment(media_type);
+                self.walk_schema(&format!("{location}/{media_type}/schema"), schema);
+            }
+        }
+    }
+
+    /// Walk all schemas in paths
+    fn walk_paths(&mut self, paths: &Value) {
+        let Value::Object(paths) = paths else {
+            return;
+        };
+        for (path, path_item) in paths {
+            let Value::Object(path_item) = path_item else {
+                continue;
+            };
+            let path = escape_pointer_segment(path);
+            for (http_method, operation) in path_item {
+                let location = format!("/paths/{path}/{http_method}");
+                if let Some(Value::Array(parameters)) = operation.get("parameters") {
+                    for (idx, parameter) in parameters.iter().enumerate() {
+                        if let Some(schema) = parameter.get("schema") {
+                            let location = format!("{location}/parameters/{idx}/schema");
+                            self.walk_schema(&location, schema);
+                        }
+                    }
+                }
+                if let Some(request_body) = operation.get("requestBody") {
+                    let location = format!("{location}/requestBody/content");
+                    self.walk_content(&location, request_body.get("content"));
+                }
+                if let Some(Value::Object(responses)) = operation.get("responses") {
+                    for (status, response) in responses {
+                        let location = format!("{location}/responses/{status}/content");
+                        self.walk_content(&location, response.get("content"));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Validate a document, checking for:
+/// * `$ref`s that do not resolve
+/// * component schemas that are not reachable from any path
+/// * duplicate operation IDs
+/// * empty schemas
+pub fn validate(openapi: &OpenApi) -> Result<(), ValidationError> {
+    let mut issues = Vec::new();
+    let mut operation_ids = HashSet::new();
+    for (_, operation) in operations(openapi) {
+        if let Some(operation_id) = &operation.operation_id {
+            if !operation_ids.insert(operation_id) {
+                issues.push(ValidationIssue::DuplicateOperationId(operation_id.clone()));
+            }
+        }
+    }
+    let doc = serde_json::to_value(openapi).expect("documents should serialize to JSON");
+    let mut walker = Walker::default();
+    if let Some(paths) = doc.get("paths") {
+        walker.walk_paths(paths);
+    }
+    // Component schemas that are reachable from paths
+    let mut reachable = BTreeSet::new();
+    let mut next_ref_idx = 0;
+    // Follow references transitively
+    while next_ref_idx < walker.refs.len() {
+        let (location, reference) = walker.refs[next_ref_idx].clone();
+        next_ref_idx += 1;
+        let target = reference
+            .strip_prefix('#')
+            .and_then(|pointer| doc.pointer(pointer));
+        let Some(target) = target else {
+            issues.push(ValidationIssue::DanglingRef {
+                location,
+                reference,
+            });
+            continue;
+        };
+        if let Some(name) = reference.strip_prefix("#/components/schemas/") {
+            if reachable.insert(name.to_owned()) {
+                walker.walk_schema(&reference[1..], target);
+            }
+        }
+    }
+    if let Some(Value::Object(schemas)) = doc.pointer("/components/schemas") {
+        for (name, schema) in schemas {
+            if reachable.contains(&escape_pointer_segment(name)) {
+                continue;
+            }
+            issues.push(ValidationIssue::UnusedComponent(name.clone()));
+            // Also check unreachable schemas for dangling refs and empty
+            // schemas
+            let mut component_walker = Walker::default();
+            let location = format!("/components/schemas/{}", escape_pointer_segment(name));
+            component_walker.walk_schema(&location, schema);
+            issues.extend(
+                component_walker
+                    .refs
+                    .into_iter()
+                    .filter_map(|(location, reference)| {
+                        let resolves = reference
+                            .strip_prefix('#')
+                            .and_then(|pointer| doc.pointer(pointer))
+                            .is_some();
+                        (!resolves).then_some(ValidationIssue::DanglingRef {
+                            location,
+                            reference,
+                        })
+                    }),
+            );
+            walker.empty_schemas.extend(component_walker.empty_schemas);
+        }
+    }
+    issues.extend(
+        walker
+            .empty_schemas
+            .into_iter()
+            .map(|location| ValidationIssue::EmptySchema { location }),
+    );
+    if issues.is_empty() {
+        Ok(())
+    } else {
+        Err(ValidationError(issues))
+    }
+}
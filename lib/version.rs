@@ -0,0 +1,75 @@
+//! Method versions, set with `#[open_api_method(since = "..", removed_in = "..")]`
+
+use std::cmp::Ordering;
+
+use utoipa::openapi::{path::Operation, OpenApi};
+
+use crate::util::retain_operations;
+
+/// Parse a version, eg. `0.5.0`, as numeric components
+fn parse_version(version: &str) -> Option<Vec<u64>> {
+    version
+        .split('.')
+        .map(|component| {
+            if component.is_empty() || !component.bytes().all(|b| b.is_ascii_digit()) {
+                None
+            } else {
+                component.parse().ok()
+            }
+        })
+        .collect()
+}
+
+/// Compare versions, where missing components are 0
+fn cmp_versions(lhs: &[u64], rhs: &[u64]) -> Ordering {
+    let len = lhs.len().max(rhs.len());
+    let component = |version: &[u64], idx: usize| version.get(idx).copied().unwrap_or(0);
+    (0..len)
+        .map(|idx| component(lhs, idx).cmp(&component(rhs, idx)))
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+/// Error for a version that is not a dot-separated list of numbers
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VersionError {
+    pub version: String,
+}
+
+impl std::fmt::Display for VersionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid version: `{}`", self.version)
+    }
+}
+
+impl std::error::Error for VersionError {}
+
+/// Version in an operation extension
+fn extension_version(operation: &Operation, name: &str) -> Option<Vec<u64>> {
+    let version = operation.extensions.as_ref()?.get(name)?.as_str()?;
+    parse_version(version)
+}
+
+/// Remove operations that do not exist at a version, ie. operations with an
+/// `x-since` version after the version, or an `x-removed-in` version at or
+/// before the version, and path items that have no remaining operations.
+/// Generated as `{Trait}Doc::openapi_at_version(version)`.
+///
+/// # Errors
+/// Returns an error if the version is not a dot-separated list of numbers,
+/// eg. `0.6.0`
+pub fn filter_version(mut openapi: OpenApi, version: &str) -> Result<OpenApi, VersionError> {
+    let Some(version) = parse_version(version) else {
+        return Err(VersionError {
+            version: version.to_owned(),
+        });
+    };
+    retain_operations(&mut openapi, |operation| {
+        let added = extension_version(operation, "x-since")
+            .is_none_or(|since| cmp_versions(&since, &version).is_le());
+        let removed = extension_version(operation, "x-removed-in")
+            .is_some_and(|removed_in| cmp_versions(&removed_in, &version).is_le());
+        added && !removed
+    });
+    Ok(openapi)
+}
@@ -1,8 +1,10 @@
 use proc_macro2::{Span, TokenStream};
 use quote::ToTokens;
-use syn::{spanned::Spanned, Attribute, Ident, ItemTrait, TraitItem, TraitItemFn};
+use syn::{
+    punctuated::Punctuated, spanned::Spanned, Attribute, Ident, ItemTrait, TraitItem, TraitItemFn,
+};
 
-use crate::parse::Ast;
+use crate::parse::{parse_extension, Ast, Extension, SecurityScheme};
 
 #[derive(Clone, Debug)]
 pub enum SchemaSource {
@@ -93,7 +95,10 @@ fn parse_method_param_attr(
 }
 
 pub struct MethodParam {
+    /// Ident of the param in the trait method
     pub ident: Ident,
+    /// Param name, including renames
+    pub name: String,
     pub ty: Box<syn::Type>,
     pub schema_source: SchemaSource,
 }
@@ -154,8 +159,11 @@ fn analyze_param(pat_type: &mut syn::PatType) -> Result<MethodParam, ParamErrors
         return Err(ParamErrors(errs));
     };
     if errs.is_empty() {
+        let name =
+            parse_jsonrpsee_argument_rename(&pat_type.attrs).unwrap_or(ident.ident.to_string());
         Ok(MethodParam {
             ident: ident.ident.clone(),
+            name,
             ty: pat_type.ty.clone(),
             schema_source: method_param_attr.schema_source.unwrap_or_default(),
         })
@@ -164,10 +172,39 @@ fn analyze_param(pat_type: &mut syn::PatType) -> Result<MethodParam, ParamErrors
     }
 }
 
+/// Example call for a method.
+/// Values are JSON.
+#[derive(Debug, Default)]
+pub struct MethodExample {
+    /// Positional params
+    pub params: Option<syn::LitStr>,
+    pub result: Option<syn::LitStr>,
+}
+
+/// Side-effect classification of a method
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SideEffects {
+    /// The method does not modify state
+    pub read_only: bool,
+    /// Repeated calls with the same params have the same effect as one call
+    pub idempotent: bool,
+    /// The method modifies state
+    pub mutating: bool,
+}
+
 /// Custom attribute for methods
 #[derive(Debug, Default)]
 pub struct MethodAttr {
     pub schema_source: Option<SchemaSource>,
+    pub examples: Vec<MethodExample>,
+    pub tags: Option<Vec<String>>,
+    pub deprecated: bool,
+    pub audience: Option<String>,
+    pub since: Option<syn::LitStr>,
+    pub removed_in: Option<syn::LitStr>,
+    pub security: Option<Vec<syn::LitStr>>,
+    pub extensions: Vec<Extension>,
+    pub side_effects: SideEffects,
     pub span: Option<Span>,
 }
 
@@ -180,13 +217,27 @@ impl MethodAttrParseError {
     }
 }
 
+/// Parse a version, eg. `0.5.0`, as numeric components
+fn parse_version(version: &str) -> Option<Vec<u64>> {
+    version
+        .split('.')
+        .map(|component| {
+            if component.is_empty() || !component.bytes().all(|b| b.is_ascii_digit()) {
+                None
+            } else {
+                component.parse().ok()
+            }
+        })
+        .collect()
+}
+
 fn parse_method_attr(attr: &Attribute) -> Option<Result<MethodAttr, MethodAttrParseError>> {
     if !attr.path().is_ident("open_api_method") {
         return None;
     }
     let mut res = MethodAttr {
-        schema_source: None,
         span: Some(attr.span()),
+        ..MethodAttr::default()
     };
     let parse_result =
         attr.parse_nested_meta(
@@ -223,6 +274,122 @@ fn parse_method_attr(attr: &Attribute) -> Option<Result<MethodAttr, MethodAttrPa
                         }
                     })
                 }
+                "audience" => {
+                    if res.audience.is_some() {
+                        let err_msg = "audience cannot be set more than once";
+                        return Err(meta.error(err_msg));
+                    }
+                    res.audience = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+                    Ok(())
+                }
+                "extension" => parse_extension(&meta, &mut res.extensions),
+                key @ ("read_only" | "idempotent" | "mutating") => {
+                    let field = match key {
+                        "read_only" => &mut res.side_effects.read_only,
+                        "idempotent" => &mut res.side_effects.idempotent,
+                        _ => &mut res.side_effects.mutating,
+                    };
+                    if *field {
+                        let err_msg = format!("{key} cannot be set more than once");
+                        return Err(meta.error(err_msg));
+                    }
+                    *field = true;
+                    let SideEffects {
+                        read_only,
+                        idempotent: _,
+                        mutating,
+                    } = res.side_effects;
+                    if read_only && mutating {
+                        return Err(meta.error("read_only and mutating cannot both be set"));
+                    }
+                    Ok(())
+                }
+                "security" => {
+                    if res.security.is_some() {
+                        let err_msg = "security cannot be set more than once";
+                        return Err(meta.error(err_msg));
+                    }
+                    let input = meta.value()?;
+                    let schemes;
+                    syn::bracketed!(schemes in input);
+                    res.security = Some(Vec::from_iter(Punctuated::<
+                        syn::LitStr,
+                        syn::token::Comma,
+                    >::parse_terminated(
+                        &schemes
+                    )?));
+                    Ok(())
+                }
+                "since" | "removed_in" => {
+                    let key = meta.path.require_ident()?.to_string();
+                    let field = if key == "since" {
+                        &mut res.since
+                    } else {
+                        &mut res.removed_in
+                    };
+                    if field.is_some() {
+                        let err_msg = format!("{key} cannot be set more than once");
+                        return Err(meta.error(err_msg));
+                    }
+                    let lit_str: syn::LitStr = meta.value()?.parse()?;
+                    if parse_version(&lit_str.value()).is_none() {
+                        let err_msg = "expected a version, eg. \"0.5.0\"";
+                        return Err(syn::Error::new(lit_str.span(), err_msg));
+                    }
+                    *field = Some(lit_str);
+                    Ok(())
+                }
+                "deprecated" => {
+                    if res.deprecated {
+                        let err_msg = "deprecated cannot be set more than once";
+                        return Err(meta.error(err_msg));
+                    }
+                    res.deprecated = true;
+                    Ok(())
+                }
+                "tags" => {
+                    if res.tags.is_some() {
+                        let err_msg = "tags cannot be set more than once";
+                        return Err(meta.error(err_msg));
+                    }
+                    let input = meta.value()?;
+                    let tags;
+                    syn::bracketed!(tags in input);
+                    res.tags = Some(
+                        Punctuated::<syn::LitStr, syn::token::Comma>::parse_terminated(&tags)?
+                            .into_iter()
+                            .map(|tag| tag.value())
+                            .collect(),
+                    );
+                    Ok(())
+                }
+                "example" => {
+                    let mut example = MethodExample::default();
+                    meta.parse_nested_meta(|meta| {
+                        let field = match meta.path.require_ident()?.to_string().as_str() {
+                            "params" => &mut example.params,
+                            "result" => &mut example.result,
+                            ident => {
+                                let err_msg = format!("unexpected key: {ident}");
+                                return Err(syn::Error::new(meta.path.span(), err_msg));
+                            }
+                        };
+                        if field.is_some() {
+                            return Err(meta.error("example values cannot be set more than once"));
+                        }
+                        let lit_str: syn::LitStr = meta.value()?.parse()?;
+                        if let Err(err) =
+                            serde_json::from_str::<serde_json::Value>(&lit_str.value())
+                        {
+                            let err_msg = format!("invalid JSON: {err}");
+                            return Err(syn::Error::new(lit_str.span(), err_msg));
+                        }
+                        *field = Some(lit_str);
+                        Ok(())
+                    })?;
+                    res.examples.push(example);
+                    Ok(())
+                }
                 ident => {
                     let err_msg = format!("unexpected key: {ident}");
                     Err(syn::Error::new(meta.path.span(), err_msg))
@@ -240,16 +407,156 @@ pub struct MethodOutput {
     pub schema_source: SchemaSource,
 }
 
+/// Skip the value of an argument that is not used for docs
+fn skip_nested_meta_value(meta: &syn::meta::ParseNestedMeta) -> syn::Result<()> {
+    if meta.input.peek(syn::Token![=]) {
+        let _: syn::Expr = meta.value()?.parse()?;
+    } else if meta.input.peek(syn::token::Paren) {
+        let _content;
+        syn::parenthesized!(_content in meta.input);
+    }
+    Ok(())
+}
+
+/// Arguments of the jsonrpsee `rpc` trait attribute that are used for docs
+#[derive(Debug, Default)]
+pub struct JsonrpseeRpcAttr {
+    pub namespace: Option<String>,
+    pub namespace_separator: Option<String>,
+}
+
+impl JsonrpseeRpcAttr {
+    /// Wire name for a method, including the namespace
+    fn method_name(&self, name: &str) -> String {
+        match &self.namespace {
+            Some(namespace) => {
+                let separator = self.namespace_separator.as_deref().unwrap_or("_");
+                format!("{namespace}{separator}{name}")
+            }
+            None => name.to_owned(),
+        }
+    }
+}
+
+/// Invalid arguments are ignored, since jsonrpsee reports them
+fn parse_jsonrpsee_rpc_attr(attrs: &[Attribute]) -> JsonrpseeRpcAttr {
+    let mut res = JsonrpseeRpcAttr::default();
+    let Some(attr) = attrs.iter().find(|attr| attr.path().is_ident("rpc")) else {
+        return res;
+    };
+    let _: syn::Result<()> = attr.parse_nested_meta(|meta| {
+        if meta.path.is_ident("namespace") {
+            res.namespace = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+        } else if meta.path.is_ident("namespace_separator") {
+            res.namespace_separator = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+        } else {
+            skip_nested_meta_value(&meta)?;
+        }
+        Ok(())
+    });
+    res
+}
+
+/// Rename from the jsonrpsee `argument` attribute.
+/// Invalid arguments are ignored, since jsonrpsee reports them
+fn parse_jsonrpsee_argument_rename(attrs: &[Attribute]) -> Option<String> {
+    let attr = attrs.iter().find(|attr| attr.path().is_ident("argument"))?;
+    let mut res = None;
+    let _: syn::Result<()> = attr.parse_nested_meta(|meta| {
+        if meta.path.is_ident("rename") {
+            res = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+        } else {
+            skip_nested_meta_value(&meta)?;
+        }
+        Ok(())
+    });
+    res
+}
+
+/// Arguments of the jsonrpsee `method` attribute that are used for docs
+#[derive(Debug, Default)]
+pub struct JsonrpseeMethodAttr {
+    pub name: Option<String>,
+    pub aliases: Vec<String>,
+    pub blocking: bool,
+    /// Params are passed by name (`param_kind = map`)
+    pub named_params: bool,
+}
+
+/// Invalid arguments are ignored, since jsonrpsee reports them
+fn parse_jsonrpsee_method_attr(attrs: &[Attribute]) -> JsonrpseeMethodAttr {
+    let mut res = JsonrpseeMethodAttr::default();
+    let Some(attr) = attrs.iter().find(|attr| attr.path().is_ident("method")) else {
+        return res;
+    };
+    let _: syn::Result<()> = attr.parse_nested_meta(|meta| {
+        if meta.path.is_ident("name") {
+            res.name = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+        } else if meta.path.is_ident("aliases") {
+            let input = meta.value()?;
+            let aliases;
+            syn::bracketed!(aliases in input);
+            res.aliases = Punctuated::<syn::LitStr, syn::token::Comma>::parse_terminated(&aliases)?
+                .into_iter()
+                .map(|alias| alias.value())
+                .collect();
+        } else if meta.path.is_ident("blocking") {
+            res.blocking = true;
+        } else if meta.path.is_ident("param_kind") {
+            res.named_params = meta.value()?.parse::<Ident>()? == "map";
+        } else {
+            skip_nested_meta_value(&meta)?;
+        }
+        Ok(())
+    });
+    res
+}
+
+/// Example for a method, with values in the same shape as the request body
+/// and response schemas.
+/// Values are serialized JSON.
+pub struct Example {
+    pub request_body: Option<String>,
+    pub result: Option<String>,
+}
+
 pub struct Method {
+    /// Ident of the trait method
     pub ident: Ident,
+    /// Method name, including the namespace
+    pub name: String,
+    /// Method aliases
+    pub aliases: Vec<String>,
     pub params: Vec<MethodParam>,
     pub output: Option<MethodOutput>,
     pub description: Option<String>,
+    pub examples: Vec<Example>,
+    pub tags: Vec<String>,
+    pub deprecated: bool,
+    /// Audience of the method, if not the default audience
+    pub audience: Option<String>,
+    /// Version that the method was added in
+    pub since: Option<String>,
+    /// Version that the method was removed in
+    pub removed_in: Option<String>,
+    /// Names of the security schemes that the method accepts, if set.
+    /// Empty if the method does not require authentication.
+    pub security: Option<Vec<String>>,
+    /// Vendor extensions for the operation
+    pub extensions: Vec<Extension>,
+    pub side_effects: SideEffects,
+    /// The method is registered as blocking
+    pub blocking: bool,
+    /// Params are passed by name
+    pub named_params: bool,
 }
 
 pub enum MethodError {
     AttrParseError(MethodAttrParseError),
     DuplicateAttr(Span),
+    InvalidVersions(syn::Error),
+    UnknownSecurityScheme(syn::Error),
+    InvalidExample(syn::Error),
     ParamError(ParamError),
 }
 
@@ -261,6 +568,9 @@ impl MethodError {
                 let err_msg = "open_api_method attribute can be used at most once";
                 syn::Error::new(span, err_msg).into_compile_error()
             }
+            Self::InvalidVersions(err) => err.into_compile_error(),
+            Self::UnknownSecurityScheme(err) => err.into_compile_error(),
+            Self::InvalidExample(err) => err.into_compile_error(),
             Self::ParamError(err) => err.into_compile_error(),
         }
     }
@@ -315,9 +625,79 @@ fn analyze_trait_item_fn_attrs(
     res_attr.unwrap_or_default()
 }
 
-fn analyze_trait_item_fn(trait_item_fn: &mut TraitItemFn) -> Result<Method, MethodErrors> {
+/// Convert an example to the shape of the request body and response schemas
+fn analyze_example(
+    params: &[MethodParam],
+    has_output: bool,
+    example: MethodExample,
+) -> Result<Example, MethodError> {
+    let request_body = match example.params {
+        None => None,
+        Some(lit_str) => {
+            // Validated when parsing the attribute
+            let serde_json::Value::Array(mut values) =
+                serde_json::from_str(&lit_str.value()).unwrap()
+            else {
+                let err_msg = "example params must be an array";
+                return Err(MethodError::InvalidExample(syn::Error::new(
+                    lit_str.span(),
+                    err_msg,
+                )));
+            };
+            if values.len() > params.len() {
+                let err_msg = format!(
+                    "expected at most {} example params, found {}",
+                    params.len(),
+                    values.len()
+                );
+                return Err(MethodError::InvalidExample(syn::Error::new(
+                    lit_str.span(),
+                    err_msg,
+                )));
+            }
+            match params.len() {
+                0 => None,
+                1 => Some(values.pop().unwrap_or_default().to_string()),
+                // Omitted params are omitted from the object
+                _ => {
+                    let object: serde_json::Map<_, _> = params
+                        .iter()
+                        .map(|param| param.name.clone())
+                        .zip(values)
+                        .collect();
+                    Some(serde_json::Value::Object(object).to_string())
+                }
+            }
+        }
+    };
+    let result = match example.result {
+        Some(lit_str) if !has_output => {
+            let err_msg = "example result is set, but the method has no output";
+            return Err(MethodError::InvalidExample(syn::Error::new(
+                lit_str.span(),
+                err_msg,
+            )));
+        }
+        result => result.map(|lit_str| lit_str.value()),
+    };
+    Ok(Example {
+        request_body,
+        result,
+    })
+}
+
+fn analyze_trait_item_fn(
+    rpc_attr: &JsonrpseeRpcAttr,
+    security_schemes: Option<&[SecurityScheme]>,
+    trait_item_fn: &mut TraitItemFn,
+) -> Result<Method, MethodErrors> {
     let mut errs = Vec::new();
     let ident = trait_item_fn.sig.ident.clone();
+    let jsonrpsee_method_attr = parse_jsonrpsee_method_attr(&trait_item_fn.attrs);
+    let name = match jsonrpsee_method_attr.name {
+        Some(name) => rpc_attr.method_name(&name),
+        None => ident.to_string(),
+    };
     let method_attr = analyze_trait_item_fn_attrs(&mut errs, trait_item_fn);
     let mut params = Vec::new();
     trait_item_fn
@@ -342,6 +722,49 @@ fn analyze_trait_item_fn(trait_item_fn: &mut TraitItemFn) -> Result<Method, Meth
             schema_source: method_attr.schema_source.unwrap_or_default(),
         }),
     };
+    let mut examples = Vec::new();
+    for example in method_attr.examples {
+        match analyze_example(&params, output.is_some(), example) {
+            Ok(example) => examples.push(example),
+            Err(err) => errs.push(err),
+        }
+    }
+    for scheme in method_attr.security.iter().flatten() {
+        let is_known = security_schemes
+            .into_iter()
+            .flatten()
+            .any(|security_scheme| security_scheme.name == scheme.value());
+        if !is_known {
+            let err_msg = format!(
+                "unknown security scheme `{}`; schemes are set with `#[open_api(security_schemes(..))]`",
+                scheme.value()
+            );
+            errs.push(MethodError::UnknownSecurityScheme(syn::Error::new(
+                scheme.span(),
+                err_msg,
+            )));
+        }
+    }
+    if let (Some(since), Some(removed_in)) = (&method_attr.since, &method_attr.removed_in) {
+        // Versions are checked when parsing the attribute
+        let since = parse_version(&since.value()).unwrap_or_default();
+        let removed_in_version = parse_version(&removed_in.value()).unwrap_or_default();
+        let len = since.len().max(removed_in_version.len());
+        let padded = |mut version: Vec<u64>| {
+            version.resize(len, 0);
+            version
+        };
+        if padded(since) >= padded(removed_in_version) {
+            let err_msg = "removed_in must be a later version than since";
+            errs.push(MethodError::InvalidVersions(syn::Error::new(
+                removed_in.span(),
+                err_msg,
+            )));
+        }
+    }
+    if !errs.is_empty() {
+        return Err(MethodErrors(errs));
+    }
     let doc_comments: Vec<_> = trait_item_fn
         .attrs
         .iter()
@@ -354,14 +777,39 @@ fn analyze_trait_item_fn(trait_item_fn: &mut TraitItemFn) -> Result<Method, Meth
     };
     Ok(Method {
         ident,
+        name,
+        aliases: jsonrpsee_method_attr.aliases,
         params,
         output,
         description,
+        examples,
+        tags: method_attr.tags.unwrap_or_default(),
+        deprecated: method_attr.deprecated,
+        audience: method_attr.audience,
+        since: method_attr.since.map(|since| since.value()),
+        removed_in: method_attr.removed_in.map(|removed_in| removed_in.value()),
+        security: method_attr
+            .security
+            .map(|security| security.iter().map(syn::LitStr::value).collect()),
+        extensions: method_attr.extensions,
+        side_effects: method_attr.side_effects,
+        blocking: jsonrpsee_method_attr.blocking,
+        named_params: jsonrpsee_method_attr.named_params,
     })
 }
 
 pub struct Model {
     pub ref_schema_tys: Vec<syn::Type>,
+    pub doc_struct: Option<Ident>,
+    pub doc_vis: Option<syn::Visibility>,
+    pub crate_path: Option<syn::Path>,
+    pub validate_test: bool,
+    pub help: bool,
+    pub request_enums: bool,
+    pub named_schemas: bool,
+    pub security_schemes: Vec<SecurityScheme>,
+    pub extensions: Vec<Extension>,
+    pub snapshot: Option<syn::LitStr>,
     pub methods: Vec<Method>,
     pub item_trait: ItemTrait,
 }
@@ -382,6 +830,7 @@ pub fn analyze(mut ast: Ast) -> Result<Model, Error> {
         Some(ref_schema_tys) => Vec::from_iter(ref_schema_tys),
         None => Vec::new(),
     };
+    let rpc_attr = parse_jsonrpsee_rpc_attr(&ast.item_trait.attrs);
     let (mut methods, mut method_errs) = (Vec::new(), Vec::new());
     ast.item_trait
         .items
@@ -393,7 +842,11 @@ pub fn analyze(mut ast: Ast) -> Result<Model, Error> {
                     .iter()
                     .any(|attr| attr.path().is_ident("method")) =>
             {
-                Some(analyze_trait_item_fn(trait_item_fn))
+                Some(analyze_trait_item_fn(
+                    &rpc_attr,
+                    ast.security_schemes.as_deref(),
+                    trait_item_fn,
+                ))
             }
             _ => None,
         })
@@ -404,6 +857,16 @@ pub fn analyze(mut ast: Ast) -> Result<Model, Error> {
     if method_errs.is_empty() {
         Ok(Model {
             ref_schema_tys,
+            doc_struct: ast.doc_struct,
+            doc_vis: ast.doc_vis,
+            crate_path: ast.crate_path,
+            validate_test: ast.validate_test,
+            help: ast.help,
+            request_enums: ast.request_enums,
+            named_schemas: ast.named_schemas,
+            security_schemes: ast.security_schemes.unwrap_or_default(),
+            extensions: ast.extensions,
+            snapshot: ast.snapshot,
             methods,
             item_trait: ast.item_trait,
         })
@@ -1,93 +1,319 @@
 use proc_macro2::TokenStream;
 use proc_macro_roids::IdentExt;
-use quote::quote;
+use quote::{quote, ToTokens};
 use syn::Ident;
 
 use crate::{
-    analyze::{MethodOutput, MethodParam, SchemaSource},
+    analyze::{Method, MethodOutput, MethodParam, SchemaSource, SideEffects},
     lower::Ir,
+    parse::Extension,
 };
 
 pub type Rust = TokenStream;
 
 /// Expression to generate a schema from a source and type
-fn schema_expr(source: &SchemaSource, ty: &syn::Type) -> TokenStream {
+fn schema_expr(crate_path: &syn::Path, source: &SchemaSource, ty: &syn::Type) -> TokenStream {
     match source {
         SchemaSource::Partial(Some(ty)) | SchemaSource::ToSchema(Some(ty)) => quote! {
-            <#ty as l2l_openapi::__utoipa::PartialSchema>::schema()
+            <#ty as #crate_path::__utoipa::PartialSchema>::schema()
         },
         SchemaSource::Partial(None) | SchemaSource::ToSchema(None) => quote! {
-            <#ty as l2l_openapi::__utoipa::PartialSchema>::schema()
+            <#ty as #crate_path::__utoipa::PartialSchema>::schema()
         },
     }
 }
 
+/// Type that a schema is generated from, for a source and type
+fn schema_ty(source: &SchemaSource, ty: &syn::Type) -> syn::Type {
+    match source {
+        SchemaSource::Partial(Some(ty)) | SchemaSource::ToSchema(Some(ty)) => ty.clone(),
+        SchemaSource::Partial(None) | SchemaSource::ToSchema(None) => ty.clone(),
+    }
+}
+
+/// Where predicates required to generate schemas, if the doc struct is
+/// generic
+fn schema_predicates(ir: &Ir) -> Vec<syn::WherePredicate> {
+    let crate_path = &ir.crate_path;
+    let mut schema_tys = Vec::new();
+    let mut predicates: Vec<syn::WherePredicate> = Vec::new();
+    for method in &ir.methods {
+        for method_param in &method.params {
+            schema_tys.push(schema_ty(&method_param.schema_source, &method_param.ty));
+        }
+        if let Some(MethodOutput { ty, schema_source }) = &method.output {
+            match schema_source {
+                SchemaSource::Partial(None) | SchemaSource::ToSchema(None) => {
+                    predicates.push(syn::parse_quote! {
+                        #ty: #crate_path::__jsonrpsee::IntoResponse
+                    });
+                    schema_tys.push(syn::parse_quote! {
+                        <#ty as #crate_path::__jsonrpsee::IntoResponse>::Output
+                    });
+                }
+                SchemaSource::Partial(Some(_)) | SchemaSource::ToSchema(Some(_)) => {
+                    schema_tys.push(schema_ty(schema_source, ty))
+                }
+            }
+        }
+    }
+    predicates.extend(schema_tys.into_iter().map(|schema_ty| {
+        syn::parse_quote! { #schema_ty: #crate_path::__utoipa::PartialSchema }
+    }));
+    predicates.extend(ir.ref_schema_tys.iter().map(|ref_schema_ty| {
+        syn::parse_quote! { #ref_schema_ty: #crate_path::__utoipa::ToSchema }
+    }));
+    // Deduplicate predicates
+    let mut predicate_strs = std::collections::HashSet::new();
+    predicates.retain(|predicate| predicate_strs.insert(predicate.to_token_stream().to_string()));
+    predicates
+}
+
 /// Expression to generate a schema from a method param
-fn method_param_schema_expr(method_param: &MethodParam) -> TokenStream {
-    schema_expr(&method_param.schema_source, &method_param.ty)
+fn method_param_schema_expr(crate_path: &syn::Path, method_param: &MethodParam) -> TokenStream {
+    schema_expr(crate_path, &method_param.schema_source, &method_param.ty)
 }
 
 /// Expression to generate a schema from a method output
-fn method_output_schema_expr(method_output: &MethodOutput) -> TokenStream {
+fn method_output_schema_expr(crate_path: &syn::Path, method_output: &MethodOutput) -> TokenStream {
     let MethodOutput { ty, schema_source } = method_output;
-    let inner_ty_expr = quote! { <#ty as l2l_openapi::__jsonrpsee::IntoResponse>::Output };
+    let inner_ty_expr = quote! { <#ty as #crate_path::__jsonrpsee::IntoResponse>::Output };
     let inner_ty: syn::Type = syn::parse2(inner_ty_expr).unwrap();
-    schema_expr(schema_source, &inner_ty)
+    schema_expr(crate_path, schema_source, &inner_ty)
+}
+
+/// Expression for a map of examples, keyed by index
+fn examples_expr<'a, I>(crate_path: &syn::Path, examples: I) -> TokenStream
+where
+    I: IntoIterator<Item = Option<&'a String>>,
+{
+    let insert_examples: TokenStream = examples
+        .into_iter()
+        .enumerate()
+        .filter_map(|(idx, value)| {
+            let idx_lit = idx.to_string();
+            let value = value?;
+            Some(quote! {
+                examples.insert(
+                    #idx_lit.to_owned(),
+                    #crate_path::__utoipa::openapi::example::ExampleBuilder::new()
+                        .value(Some(
+                            #crate_path::__serde_json::from_str(#value).unwrap()
+                        ))
+                        .build()
+                );
+            })
+        })
+        .collect();
+    quote! {
+        {
+            let mut examples = ::std::collections::BTreeMap::<
+                ::std::string::String,
+                #crate_path::__utoipa::openapi::example::Example,
+            >::new();
+            #insert_examples
+            examples
+        }
+    }
+}
+
+/// Expression for an object schema, with a property for each method param
+fn params_object_schema_expr(crate_path: &syn::Path, method: &Method) -> TokenStream {
+    let set_properties: TokenStream = method
+        .params
+        .iter()
+        .map(|method_param| {
+            let name_lit = &method_param.name;
+            let schema_expr = method_param_schema_expr(crate_path, method_param);
+            quote! {
+                schema.properties.insert(
+                    #name_lit.to_owned(),
+                    #schema_expr
+                );
+            }
+        })
+        .collect();
+    quote! {
+        {
+            let mut schema = #crate_path::__utoipa::openapi::Object::new();
+            #set_properties
+            #crate_path::__utoipa::openapi::Schema::Object(schema)
+        }
+    }
+}
+
+/// Component names for the params and result of a method, eg.
+/// `GetHeadersParams` and `GetHeadersResult`
+fn named_schema_names(method: &Method) -> (String, String) {
+    let ident = to_pascal_case(&method.ident);
+    (format!("{ident}Params"), format!("{ident}Result"))
+}
+
+/// Operation description, with notes for the versions that the method was
+/// added and removed in
+fn operation_description(method: &Method) -> Option<String> {
+    let mut notes = Vec::new();
+    if let Some(since) = &method.since {
+        notes.push(format!("Available since version {since}."));
+    }
+    if let Some(removed_in) = &method.removed_in {
+        notes.push(format!("Removed in version {removed_in}."));
+    }
+    let notes = (!notes.is_empty()).then(|| notes.join(" "));
+    match (&method.description, notes) {
+        (Some(description), Some(notes)) => Some(format!("{description}\n\n{notes}")),
+        (description, notes) => description.clone().or(notes),
+    }
+}
+
+/// `ExtensionsBuilder::add` call for an extension
+fn extension_expr(crate_path: &syn::Path, extension: &Extension) -> TokenStream {
+    let Extension { name, value } = extension;
+    quote! {
+        .add(#name, #crate_path::__serde_json::from_str::<#crate_path::__serde_json::Value>(#value).unwrap())
+    }
 }
 
 fn gen_doc(ir: &Ir) -> Rust {
     let Ir {
         ref_schema_tys,
+        doc_struct,
+        doc_vis,
+        crate_path,
+        validate_test: _,
+        help: _,
+        request_enums: _,
+        named_schemas,
+        security_schemes,
+        extensions,
+        snapshot: _,
         methods,
-        item_trait,
+        doc_generics,
+        item_trait: _,
     } = ir;
 
     let add_paths: TokenStream = methods
         .iter()
         .map(|method| {
-            // TODO: Do not use rust method name
-            let ident_str_lit = &method.ident.to_string();
+            let name_lit = &method.name;
 
             let operation = {
-                let set_description = method.description.as_ref().map(|description| {
+                let set_description = operation_description(method).map(|description| {
                     quote! {
                         operation.description = Some(#description.to_owned());
                     }
                 });
+                let set_tags = (!method.tags.is_empty()).then(|| {
+                    let tags = &method.tags;
+                    quote! {
+                        operation.tags = Some(vec![#(#tags.to_owned()),*]);
+                    }
+                });
+                let set_deprecated = method.deprecated.then(|| {
+                    quote! {
+                        operation.deprecated =
+                            Some(#crate_path::__utoipa::openapi::Deprecated::True);
+                    }
+                });
+                // Methods accept any of the trait security schemes, if not set
+                let security = match &method.security {
+                    Some(security) => Some(security.clone()),
+                    None if security_schemes.is_empty() => None,
+                    None => Some(
+                        security_schemes
+                            .iter()
+                            .map(|scheme| scheme.name.to_string())
+                            .collect(),
+                    ),
+                };
+                let set_security = security.map(|security| {
+                    quote! {
+                        operation.security = Some(vec![#(
+                            #crate_path::__utoipa::openapi::security::SecurityRequirement::new(
+                                #security,
+                                ::std::vec::Vec::<::std::string::String>::new(),
+                            )
+                        ),*]);
+                    }
+                });
+                let mut add_extensions = Vec::new();
+                if !method.aliases.is_empty() {
+                    let aliases = &method.aliases;
+                    add_extensions.push(quote! { .add("x-aliases", vec![#(#aliases),*]) });
+                }
+                if !method.params.is_empty() {
+                    let param_names = method.params.iter().map(|param| &param.name);
+                    add_extensions.push(quote! { .add("x-param-names", vec![#(#param_names),*]) });
+                }
+                if method.named_params {
+                    add_extensions.push(quote! { .add("x-param-structure", "by-name") });
+                }
+                if let Some(audience) = &method.audience {
+                    add_extensions.push(quote! { .add("x-audience", #audience) });
+                }
+                if let Some(since) = &method.since {
+                    add_extensions.push(quote! { .add("x-since", #since) });
+                }
+                if let Some(removed_in) = &method.removed_in {
+                    add_extensions.push(quote! { .add("x-removed-in", #removed_in) });
+                }
+                let SideEffects {
+                    read_only,
+                    idempotent,
+                    mutating,
+                } = method.side_effects;
+                for (name, marker) in [
+                    ("x-read-only", read_only),
+                    ("x-idempotent", idempotent),
+                    ("x-mutating", mutating),
+                ] {
+                    if marker {
+                        add_extensions.push(quote! { .add(#name, true) });
+                    }
+                }
+                add_extensions.extend(
+                    method
+                        .extensions
+                        .iter()
+                        .map(|extension| extension_expr(crate_path, extension)),
+                );
+                let set_extensions = if add_extensions.is_empty() {
+                    None
+                } else {
+                    Some(quote! {
+                        operation.extensions = Some(
+                            #crate_path::__utoipa::openapi::extensions::ExtensionsBuilder::new()
+                                #(#add_extensions)*
+                                .build()
+                        );
+                    })
+                };
                 let set_request_body = if !method.params.is_empty() {
-                    // TODO: set name
                     let content_schema = if method.params.len() == 1 {
-                        method_param_schema_expr(&method.params[0])
-                    } else {
-                        let set_properties: TokenStream = method
-                            .params
-                            .iter()
-                            .map(|method_param| {
-                                let ident_str_lit = method_param.ident.to_string();
-                                let schema_expr = method_param_schema_expr(method_param);
-                                quote! {
-                                    schema.properties.insert(
-                                        #ident_str_lit.to_owned(),
-                                        #schema_expr
-                                    );
-                                }
-                            })
-                            .collect();
+                        method_param_schema_expr(crate_path, &method.params[0])
+                    } else if *named_schemas {
+                        let (params_name, _) = named_schema_names(method);
                         quote! {
-                            {
-                                let mut schema = utoipa::openapi::Object::new();
-                                #set_properties
-                                l2l_openapi::__utoipa::openapi::Schema::Object(schema)
-                            }
+                            #crate_path::__utoipa::openapi::Ref::from_schema_name(#params_name)
                         }
+                    } else {
+                        params_object_schema_expr(crate_path, method)
                     };
+                    let examples = examples_expr(
+                        crate_path,
+                        method
+                            .examples
+                            .iter()
+                            .map(|example| example.request_body.as_ref()),
+                    );
                     Some(quote! {
                         operation.request_body = {
                             let mut request_body =
-                            l2l_openapi::__utoipa::openapi::request_body::RequestBody::new();
+                            #crate_path::__utoipa::openapi::request_body::RequestBody::new();
                             let content_schema = #content_schema;
-                            let content = l2l_openapi::__utoipa::openapi::ContentBuilder::new()
+                            let content = #crate_path::__utoipa::openapi::ContentBuilder::new()
                                 .schema(Some(content_schema))
+                                .examples_from_iter(#examples)
                                 .build();
                             request_body.content.insert("application/json".to_owned(), content);
                             Some(request_body)
@@ -99,27 +325,43 @@ fn gen_doc(ir: &Ir) -> Rust {
                 let set_responses =
                 // TODO: Handle errors
                 method.output.as_ref().map(|output| {
-                    let schema_expr = method_output_schema_expr(output);
+                    let schema_expr = if *named_schemas {
+                        let (_, result_name) = named_schema_names(method);
+                        quote! {
+                            #crate_path::__utoipa::openapi::Ref::from_schema_name(#result_name)
+                        }
+                    } else {
+                        method_output_schema_expr(crate_path, output)
+                    };
+                    let examples = examples_expr(
+                        crate_path,
+                        method.examples.iter().map(|example| example.result.as_ref()),
+                    );
                     quote! {
                         let response = {
-                            let content = l2l_openapi::__utoipa::openapi::ContentBuilder::new()
+                            let content = #crate_path::__utoipa::openapi::ContentBuilder::new()
                                 .schema(Some(#schema_expr))
+                                .examples_from_iter(#examples)
                                 .build();
-                            l2l_openapi::__utoipa::openapi::ResponseBuilder::new()
+                            #crate_path::__utoipa::openapi::ResponseBuilder::new()
                                 .content("application/json".to_owned(), content)
                                 .build()
                         };
                         operation.responses.responses.insert(
                             "200".to_owned(),
-                            l2l_openapi::__utoipa::openapi::RefOr::T(response)
+                            #crate_path::__utoipa::openapi::RefOr::T(response)
                         );
                     }
                 });
                 quote! {
                     {
-                        let mut operation = l2l_openapi::__utoipa::openapi::path::Operation::new();
+                        let mut operation = #crate_path::__utoipa::openapi::path::Operation::new();
                         #set_description
-                        operation.operation_id = Some(#ident_str_lit.to_owned());
+                        operation.operation_id = Some(#name_lit.to_owned());
+                        #set_tags
+                        #set_deprecated
+                        #set_security
+                        #set_extensions
                         #set_request_body
                         #set_responses
                         operation
@@ -129,64 +371,882 @@ fn gen_doc(ir: &Ir) -> Rust {
 
             let path_item = quote! {
                 {
-                    let mut path_item_builder = l2l_openapi::__utoipa::openapi::PathItem::builder();
+                    let mut path_item_builder = #crate_path::__utoipa::openapi::PathItem::builder();
                     let operation = #operation;
                     path_item_builder = path_item_builder.operation(
-                        l2l_openapi::__utoipa::openapi::path::HttpMethod::Post,
+                        #crate_path::__utoipa::openapi::path::HttpMethod::Post,
                         operation
                     );
                     path_item_builder.build()
                 }
             };
 
-            quote! { .path(#ident_str_lit, #path_item) }
+            quote! { .path(#name_lit, #path_item) }
         })
         .collect();
 
-    let add_ref_schemas: TokenStream = ref_schema_tys
+    let mut add_ref_schemas: TokenStream = ref_schema_tys
         .iter()
         .map(|ref_schema_ty| {
             quote! { .schema_from::<#ref_schema_ty>() }
         })
         .collect();
+    if *named_schemas {
+        for method in methods {
+            let (params_name, result_name) = named_schema_names(method);
+            if method.params.len() > 1 {
+                let params_ident = Ident::new(&params_name, method.ident.span());
+                add_ref_schemas.extend(quote! { .schema_from::<#params_ident>() });
+            }
+            if let Some(output) = &method.output {
+                let schema_expr = method_output_schema_expr(crate_path, output);
+                add_ref_schemas.extend(quote! { .schema(#result_name, #schema_expr) });
+            }
+        }
+    }
+
+    let add_security_schemes: TokenStream = security_schemes
+        .iter()
+        .map(|scheme| {
+            let name = scheme.name.to_string();
+            let http_auth_scheme = &scheme.http_auth_scheme;
+            quote! {
+                .security_scheme(
+                    #name,
+                    #crate_path::__utoipa::openapi::security::SecurityScheme::Http(
+                        #crate_path::__utoipa::openapi::security::Http::new(
+                            #crate_path::__utoipa::openapi::security::HttpAuthScheme::#http_auth_scheme
+                        )
+                    )
+                )
+            }
+        })
+        .collect();
+
+    let set_extensions = (!extensions.is_empty()).then(|| {
+        let add_extensions = extensions
+            .iter()
+            .map(|extension| extension_expr(crate_path, extension));
+        quote! {
+            openapi.extensions = Some(
+                #crate_path::__utoipa::openapi::extensions::ExtensionsBuilder::new()
+                    #(#add_extensions)*
+                    .build()
+            );
+        }
+    });
 
-    // ident of the generated struct
-    let ident = &item_trait.ident;
-    let struct_ident_suffix = Ident::new("Doc", ident.span());
-    let struct_ident = ident.append(struct_ident_suffix);
-    let struct_vis = &item_trait.vis;
+    let mut_token = set_extensions.is_some().then(|| quote! { mut });
+
+    let struct_ident = doc_struct;
+    let struct_vis = doc_vis;
+
+    let struct_def = if doc_generics.params.is_empty() {
+        quote! { #struct_vis struct #struct_ident; }
+    } else {
+        // Bounds are only required on the impl
+        let struct_params = doc_generics.params.iter().map(|param| match param {
+            syn::GenericParam::Lifetime(param) => {
+                let lifetime = &param.lifetime;
+                quote! { #lifetime }
+            }
+            syn::GenericParam::Type(param) => {
+                let ident = &param.ident;
+                match &param.default {
+                    Some(default) => quote! { #ident = #default },
+                    None => quote! { #ident },
+                }
+            }
+            syn::GenericParam::Const(param) => quote! { #param },
+        });
+        let phantom_tys = doc_generics.params.iter().filter_map(|param| match param {
+            syn::GenericParam::Lifetime(param) => {
+                let lifetime = &param.lifetime;
+                Some(quote! { &#lifetime () })
+            }
+            syn::GenericParam::Type(param) => {
+                let ident = &param.ident;
+                Some(quote! { #ident })
+            }
+            syn::GenericParam::Const(_) => None,
+        });
+        quote! {
+            #struct_vis struct #struct_ident<#(#struct_params),*>(
+                ::core::marker::PhantomData<fn() -> (#(#phantom_tys,)*)>
+            );
+        }
+    };
+
+    let mut impl_generics = doc_generics.clone();
+    if !impl_generics.params.is_empty() {
+        impl_generics
+            .make_where_clause()
+            .predicates
+            .extend(schema_predicates(ir));
+    }
+    let (impl_generics, ty_generics, where_clause) = impl_generics.split_for_impl();
 
     quote! {
-        #struct_vis struct #struct_ident;
+        #struct_def
 
-        impl utoipa::OpenApi for #struct_ident {
-            fn openapi() -> l2l_openapi::__utoipa::openapi::OpenApi {
-                let paths = l2l_openapi::__utoipa::openapi::PathsBuilder::new()
+        impl #impl_generics #crate_path::__utoipa::OpenApi for #struct_ident #ty_generics #where_clause {
+            fn openapi() -> #crate_path::__utoipa::openapi::OpenApi {
+                let paths = #crate_path::__utoipa::openapi::PathsBuilder::new()
                     #add_paths
                     .build();
-                let components = l2l_openapi::__utoipa::openapi::ComponentsBuilder::new()
+                let components = #crate_path::__utoipa::openapi::ComponentsBuilder::new()
                     #add_ref_schemas
+                    #add_security_schemes
                     .build();
-                l2l_openapi::__utoipa::openapi::OpenApiBuilder::new()
-                .paths(paths)
-                .components(Some(components))
-                .build()
+                let #mut_token openapi = #crate_path::__utoipa::openapi::OpenApiBuilder::new()
+                    .paths(paths)
+                    .components(Some(components))
+                    .build();
+                #set_extensions
+                openapi
+            }
+        }
+
+        impl #impl_generics #struct_ident #ty_generics #where_clause {
+            /// Document with only the methods for one of the audiences
+            #struct_vis fn openapi_for(audiences: &[&str]) -> #crate_path::__utoipa::openapi::OpenApi {
+                #crate_path::filter_audiences(
+                    <Self as #crate_path::__utoipa::OpenApi>::openapi(),
+                    audiences,
+                )
             }
+
+            /// Document with only the methods that exist at a version, eg.
+            /// `"0.6.0"`.
+            ///
+            /// # Errors
+            /// Returns an error if the version is not a dot-separated list of
+            /// numbers
+            #struct_vis fn openapi_at_version(
+                version: &str,
+            ) -> ::core::result::Result<#crate_path::__utoipa::openapi::OpenApi, #crate_path::VersionError> {
+                #crate_path::filter_version(
+                    <Self as #crate_path::__utoipa::OpenApi>::openapi(),
+                    version,
+                )
+            }
+        }
+    }
+}
+
+/// Format a type for help text
+fn ty_string(ty: &syn::Type) -> String {
+    ty.to_token_stream()
+        .to_string()
+        .replace(" :: ", "::")
+        .replace(":: ", "::")
+        .replace(" < ", "<")
+        .replace(" <", "<")
+        .replace(" >", ">")
+        .replace(" ,", ",")
+        .replace("& ", "&")
+}
+
+/// The type argument of `Option<T>`, `Result<T, E>` or `RpcResult<T>`
+fn wrapped_ty<'a>(ty: &'a syn::Type, wrappers: &[&str]) -> Option<&'a syn::Type> {
+    let syn::Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    if !wrappers.iter().any(|wrapper| segment.ident == wrapper) {
+        return None;
+    }
+    let syn::PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    match args.args.first()? {
+        syn::GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+    }
+}
+
+/// Usage line for a method, eg. `get_headers start ( count )`
+fn help_usage(method: &Method) -> String {
+    let mut res = method.name.clone();
+    for param in &method.params {
+        if wrapped_ty(&param.ty, &["Option"]).is_some() {
+            res.push_str(&format!(" ( {} )", param.name));
+        } else {
+            res.push_str(&format!(" {}", param.name));
+        }
+    }
+    res
+}
+
+/// Full help text for a method
+fn help_text(method: &Method) -> String {
+    let mut res = help_usage(method);
+    res.push('\n');
+    if let Some(description) = &method.description {
+        res.push('\n');
+        res.push_str(description);
+        res.push('\n');
+    }
+    if !method.aliases.is_empty() {
+        res.push_str(&format!("\nAliases: {}\n", method.aliases.join(", ")));
+    }
+    if !method.params.is_empty() {
+        res.push_str("\nArguments:\n");
+        let name_width = method
+            .params
+            .iter()
+            .map(|param| param.name.len())
+            .max()
+            .unwrap_or_default();
+        for (idx, param) in method.params.iter().enumerate() {
+            let required = if wrapped_ty(&param.ty, &["Option"]).is_some() {
+                "optional"
+            } else {
+                "required"
+            };
+            res.push_str(&format!(
+                "{}. {:name_width$}    ({}, {required})\n",
+                idx + 1,
+                param.name,
+                ty_string(&param.ty)
+            ));
+        }
+    }
+    if let Some(output) = &method.output {
+        let ty = wrapped_ty(&output.ty, &["RpcResult", "Result"]).unwrap_or(&output.ty);
+        res.push_str(&format!("\nResult:\n{}\n", ty_string(ty)));
+    }
+    res
+}
+
+/// Generate a `METHODS` const with runtime metadata for each method
+fn gen_metadata(ir: &Ir) -> Rust {
+    let Ir {
+        ref_schema_tys: _,
+        doc_struct,
+        doc_vis,
+        crate_path,
+        validate_test: _,
+        help: _,
+        request_enums: _,
+        named_schemas: _,
+        security_schemes: _,
+        extensions: _,
+        snapshot: _,
+        methods,
+        doc_generics,
+        item_trait: _,
+    } = ir;
+    let option_expr = |value: Option<&String>| match value {
+        Some(value) => quote! { ::core::option::Option::Some(#value) },
+        None => quote! { ::core::option::Option::None },
+    };
+    let method_infos = methods.iter().map(|method| {
+        let Method {
+            ident: _,
+            name,
+            aliases,
+            params,
+            output,
+            description,
+            examples: _,
+            tags,
+            deprecated,
+            audience,
+            since: _,
+            removed_in: _,
+            security: _,
+            extensions: _,
+            side_effects,
+            blocking,
+            named_params,
+        } = method;
+        let SideEffects {
+            read_only,
+            idempotent,
+            mutating,
+        } = side_effects;
+        let param_infos = params.iter().map(|param| {
+            let name = &param.name;
+            let ty = ty_string(&param.ty);
+            quote! {
+                #crate_path::ParamInfo {
+                    name: #name,
+                    ty: #ty,
+                }
+            }
+        });
+        let result_ty = option_expr(
+            output
+                .as_ref()
+                .map(|output| {
+                    let ty = wrapped_ty(&output.ty, &["RpcResult", "Result"]).unwrap_or(&output.ty);
+                    ty_string(ty)
+                })
+                .as_ref(),
+        );
+        let description = option_expr(description.as_ref());
+        let audience = match audience {
+            Some(audience) => quote! { #audience },
+            None => quote! { #crate_path::DEFAULT_AUDIENCE },
+        };
+        quote! {
+            #crate_path::MethodInfo {
+                name: #name,
+                aliases: &[#(#aliases),*],
+                params: &[#(#param_infos),*],
+                result_ty: #result_ty,
+                description: #description,
+                tags: &[#(#tags),*],
+                deprecated: #deprecated,
+                audience: #audience,
+                flags: #crate_path::MethodFlags {
+                    blocking: #blocking,
+                    named_params: #named_params,
+                    read_only: #read_only,
+                    idempotent: #idempotent,
+                    mutating: #mutating,
+                },
+            }
+        }
+    });
+    let (impl_generics, ty_generics, where_clause) = doc_generics.split_for_impl();
+    quote! {
+        impl #impl_generics #doc_struct #ty_generics #where_clause {
+            /// Runtime metadata for each method
+            #doc_vis const METHODS: &'static [#crate_path::MethodInfo] = &[#(#method_infos),*];
         }
     }
 }
 
+/// Generate a `help` function and RPC module for the doc struct
+fn gen_help(ir: &Ir) -> Option<Rust> {
+    let Ir {
+        ref_schema_tys: _,
+        doc_struct,
+        doc_vis,
+        crate_path,
+        validate_test: _,
+        help,
+        request_enums: _,
+        named_schemas: _,
+        security_schemes: _,
+        extensions: _,
+        snapshot: _,
+        methods,
+        doc_generics,
+        item_trait: _,
+    } = ir;
+    if !help {
+        return None;
+    }
+    let summary: String = methods
+        .iter()
+        .map(|method| format!("{}\n", help_usage(method)))
+        .collect();
+    let match_arms = methods.iter().map(|method| {
+        let names = std::iter::once(&method.name).chain(&method.aliases);
+        let text = help_text(method);
+        quote! {
+            ::core::option::Option::Some(#(#names)|*) => ::core::option::Option::Some(#text),
+        }
+    });
+    let (impl_generics, ty_generics, where_clause) = doc_generics.split_for_impl();
+    Some(quote! {
+        impl #impl_generics #doc_struct #ty_generics #where_clause {
+            /// Help text for a method, or a usage summary of every method
+            /// if no method is specified. Returns `None` for unknown methods.
+            #doc_vis fn help(method: ::core::option::Option<&str>)
+                -> ::core::option::Option<&'static str>
+            {
+                match method {
+                    ::core::option::Option::None => ::core::option::Option::Some(#summary),
+                    #(#match_arms)*
+                    ::core::option::Option::Some(_) => ::core::option::Option::None,
+                }
+            }
+
+            /// RPC module with a `help` method, that returns the text from
+            /// [`Self::help`]
+            #doc_vis fn help_module() -> #crate_path::__jsonrpsee::RpcModule<()> {
+                #crate_path::help_module(Self::help)
+            }
+        }
+    })
+}
+
+/// Convert a snake case ident to pascal case
+fn to_pascal_case(ident: &Ident) -> Ident {
+    let ident_str = ident.to_string();
+    let res: String = ident_str
+        .strip_prefix("r#")
+        .unwrap_or(&ident_str)
+        .split('_')
+        .flat_map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|c| c.to_ascii_uppercase())
+                .into_iter()
+                .chain(chars)
+        })
+        .collect();
+    Ident::new(&res, ident.span())
+}
+
+/// Generate `{Trait}Request` and `{Trait}Response` enums
+fn gen_request_enums(ir: &Ir) -> Option<Rust> {
+    let Ir {
+        ref_schema_tys: _,
+        doc_struct: _,
+        doc_vis,
+        crate_path,
+        validate_test: _,
+        help: _,
+        request_enums,
+        named_schemas: _,
+        security_schemes: _,
+        extensions: _,
+        snapshot: _,
+        methods,
+        doc_generics: _,
+        item_trait,
+    } = ir;
+    if !request_enums {
+        return None;
+    }
+    let trait_ident = &item_trait.ident;
+    let request_ident = trait_ident.append(Ident::new("Request", trait_ident.span()));
+    let response_ident = trait_ident.append(Ident::new("Response", trait_ident.span()));
+    let serde_json = quote! { #crate_path::__serde_json };
+    let serde = quote! { #crate_path::__serde };
+    let helpers = quote! { #crate_path::request };
+    let variant_idents: Vec<_> = methods
+        .iter()
+        .map(|method| to_pascal_case(&method.ident))
+        .collect();
+    let names: Vec<_> = methods.iter().map(|method| &method.name).collect();
+    let request_variants = methods
+        .iter()
+        .zip(&variant_idents)
+        .map(|(method, variant)| {
+            let doc = method
+                .description
+                .as_ref()
+                .map(|description| quote! { #[doc = #description] });
+            if method.params.is_empty() {
+                return quote! { #doc #variant, };
+            }
+            let fields = method.params.iter().map(|param| {
+                let MethodParam { ident, ty, .. } = param;
+                quote! { #ident: #ty }
+            });
+            quote! { #doc #variant { #(#fields),* }, }
+        });
+    let request_patterns: Vec<_> = methods
+        .iter()
+        .zip(&variant_idents)
+        .map(|(method, variant)| {
+            let param_idents = method.params.iter().map(|param| &param.ident);
+            if method.params.is_empty() {
+                quote! { Self::#variant }
+            } else {
+                quote! { Self::#variant { #(#param_idents),* } }
+            }
+        })
+        .collect();
+    let from_call_arms = methods.iter().zip(&request_patterns).map(|(method, pattern)| {
+        let names = std::iter::once(&method.name).chain(&method.aliases);
+        let name = &method.name;
+        let named_params = method.named_params;
+        let read_params = method.params.iter().map(|param| {
+            let MethodParam {
+                ident, name, ty, ..
+            } = param;
+            quote! { let #ident: #ty = params.next(#name)?; }
+        });
+        let mut_token = (!method.params.is_empty()).then(|| quote! { mut });
+        quote! {
+            #(#names)|* => {
+                let #mut_token params = #helpers::CallParams::new(#name, params, #named_params)?;
+                #(#read_params)*
+                params.finish(#name)?;
+                ::core::result::Result::Ok(#pattern)
+            }
+        }
+    });
+    let params_arms = methods
+        .iter()
+        .zip(&request_patterns)
+        .map(|(method, pattern)| {
+            let named_params = method.named_params;
+            let params = method.params.iter().map(|param| {
+                let MethodParam { ident, name, .. } = param;
+                quote! { (#name, #helpers::to_value(#ident)) }
+            });
+            quote! {
+                #pattern => #helpers::call_params(::std::vec![#(#params),*], #named_params),
+            }
+        });
+    let from_result_arms = methods
+        .iter()
+        .zip(&variant_idents)
+        .map(|(method, variant)| {
+            let names = std::iter::once(&method.name).chain(&method.aliases);
+            quote! {
+                #(#names)|* => #serde_json::from_value(result).map(Self::#variant),
+            }
+        });
+    let response_variants = methods
+        .iter()
+        .zip(&variant_idents)
+        .map(|(method, variant)| {
+            let result_ty = match &method.output {
+                Some(MethodOutput { ty, .. }) => quote! {
+                    <#ty as #crate_path::__jsonrpsee::IntoResponse>::Output
+                },
+                None => quote! { () },
+            };
+            quote! { #variant(#result_ty), }
+        });
+    let request_doc = format!("Call of a method of [`{trait_ident}`]");
+    let response_doc = format!("Result of a method of [`{trait_ident}`]");
+    Some(quote! {
+        #[doc = #request_doc]
+        #doc_vis enum #request_ident {
+            #(#request_variants)*
+        }
+
+        impl #request_ident {
+            /// Method name
+            #doc_vis fn method(&self) -> &'static str {
+                match self {
+                    #(Self::#variant_idents { .. } => #names,)*
+                }
+            }
+
+            /// Parse a call from a method name or alias, and params
+            #doc_vis fn from_call(
+                method: &str,
+                params: #serde_json::Value,
+            ) -> ::core::result::Result<Self, #serde_json::Error> {
+                match method {
+                    #(#from_call_arms)*
+                    _ => ::core::result::Result::Err(#helpers::unknown_method(method)),
+                }
+            }
+
+            /// Params, as an array if positional, or an object if named
+            #doc_vis fn params(&self) -> ::core::result::Result<#serde_json::Value, #serde_json::Error> {
+                match self {
+                    #(#params_arms)*
+                }
+            }
+        }
+
+        impl #serde::Serialize for #request_ident {
+            fn serialize<S>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error>
+            where
+                S: #serde::Serializer,
+            {
+                let params = self.params().map_err(<S::Error as #serde::ser::Error>::custom)?;
+                #serde::Serialize::serialize(
+                    &#helpers::call_value(self.method(), params),
+                    serializer,
+                )
+            }
+        }
+
+        impl<'de> #serde::Deserialize<'de> for #request_ident {
+            fn deserialize<D>(deserializer: D) -> ::core::result::Result<Self, D::Error>
+            where
+                D: #serde::Deserializer<'de>,
+            {
+                let call = <#serde_json::Value as #serde::Deserialize>::deserialize(deserializer)?;
+                let (method, params) = #helpers::split_call(call)
+                    .map_err(<D::Error as #serde::de::Error>::custom)?;
+                Self::from_call(&method, params).map_err(<D::Error as #serde::de::Error>::custom)
+            }
+        }
+
+        #[doc = #response_doc]
+        #doc_vis enum #response_ident {
+            #(#response_variants)*
+        }
+
+        impl #response_ident {
+            /// Method name
+            #doc_vis fn method(&self) -> &'static str {
+                match self {
+                    #(Self::#variant_idents(_) => #names,)*
+                }
+            }
+
+            /// Parse a result from a method name or alias, and result
+            #doc_vis fn from_result(
+                method: &str,
+                result: #serde_json::Value,
+            ) -> ::core::result::Result<Self, #serde_json::Error> {
+                match method {
+                    #(#from_result_arms)*
+                    _ => ::core::result::Result::Err(#helpers::unknown_method(method)),
+                }
+            }
+        }
+
+        /// Serialized as the result
+        impl #serde::Serialize for #response_ident {
+            fn serialize<S>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error>
+            where
+                S: #serde::Serializer,
+            {
+                match self {
+                    #(Self::#variant_idents(result) => #serde::Serialize::serialize(result, serializer),)*
+                }
+            }
+        }
+    })
+}
+
+/// Generate a `{Method}Params` struct for each method with more than one
+/// param, and a `{Method}Result` type alias for each method with a result
+fn gen_named_schemas(ir: &Ir) -> Option<Rust> {
+    let Ir {
+        ref_schema_tys: _,
+        doc_struct: _,
+        doc_vis,
+        crate_path,
+        validate_test: _,
+        help: _,
+        request_enums: _,
+        named_schemas,
+        security_schemes: _,
+        extensions: _,
+        snapshot: _,
+        methods,
+        doc_generics: _,
+        item_trait,
+    } = ir;
+    if !named_schemas {
+        return None;
+    }
+    let trait_ident = &item_trait.ident;
+    let serde = quote! { #crate_path::__serde };
+    let serde_crate = serde.to_string().replace(' ', "");
+    let utoipa = quote! { #crate_path::__utoipa };
+    let items = methods.iter().map(|method| {
+        let (params_name, result_name) = named_schema_names(method);
+        let method_ident = &method.ident;
+        let params_struct = (method.params.len() > 1).then(|| {
+            let params_ident = Ident::new(&params_name, method_ident.span());
+            let params_doc = format!("Params of [`{trait_ident}::{method_ident}`]");
+            let fields = method.params.iter().map(|param| {
+                let MethodParam {
+                    ident, name, ty, ..
+                } = param;
+                let default = wrapped_ty(ty, &["Option"]).map(|_| quote! { #[serde(default)] });
+                quote! {
+                    #[serde(rename = #name)]
+                    #default
+                    pub #ident: #ty
+                }
+            });
+            let schema_expr = params_object_schema_expr(crate_path, method);
+            quote! {
+                #[doc = #params_doc]
+                #[derive(#serde::Deserialize, #serde::Serialize)]
+                #[serde(crate = #serde_crate)]
+                #doc_vis struct #params_ident {
+                    #(#fields),*
+                }
+
+                impl #utoipa::PartialSchema for #params_ident {
+                    fn schema() -> #utoipa::openapi::RefOr<#utoipa::openapi::Schema> {
+                        #utoipa::openapi::RefOr::T(#schema_expr)
+                    }
+                }
+
+                impl #utoipa::ToSchema for #params_ident {
+                    fn name() -> ::std::borrow::Cow<'static, str> {
+                        ::std::borrow::Cow::Borrowed(#params_name)
+                    }
+                }
+            }
+        });
+        let result_alias = method.output.as_ref().map(|MethodOutput { ty, .. }| {
+            let result_ident = Ident::new(&result_name, method_ident.span());
+            let result_doc = format!("Result of [`{trait_ident}::{method_ident}`]");
+            quote! {
+                #[doc = #result_doc]
+                #doc_vis type #result_ident =
+                    <#ty as #crate_path::__jsonrpsee::IntoResponse>::Output;
+            }
+        });
+        quote! {
+            #params_struct
+
+            #result_alias
+        }
+    });
+    Some(quote! { #(#items)* })
+}
+
+/// Convert an ident to snake case
+fn to_snake_case(ident: &Ident) -> Ident {
+    let mut res = String::new();
+    for (idx, c) in ident.to_string().chars().enumerate() {
+        if c.is_uppercase() {
+            if idx != 0 && !res.ends_with('_') {
+                res.push('_');
+            }
+            res.extend(c.to_lowercase());
+        } else {
+            res.push(c);
+        }
+    }
+    Ident::new(&res, ident.span())
+}
+
+/// Generate tests for the doc struct
+fn gen_tests(ir: &Ir) -> Option<Rust> {
+    let Ir {
+        ref_schema_tys: _,
+        doc_struct,
+        doc_vis: _,
+        crate_path,
+        validate_test,
+        help: _,
+        request_enums: _,
+        named_schemas: _,
+        security_schemes: _,
+        extensions: _,
+        snapshot,
+        methods: _,
+        doc_generics: _,
+        item_trait: _,
+    } = ir;
+    let validate_test = validate_test.then(|| {
+        quote! {
+            #[cfg(test)]
+            #[test]
+            fn validate() {
+                if let Err(err) = #crate_path::validate(&openapi()) {
+                    panic!("{err}")
+                }
+            }
+        }
+    });
+    let snapshot_test = snapshot.as_ref().map(|snapshot| {
+        quote! {
+            #[cfg(test)]
+            #[test]
+            fn snapshot() {
+                let path = ::std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join(#snapshot);
+                if let Err(err) = #crate_path::check_snapshot(&openapi(), path) {
+                    panic!("{err}")
+                }
+            }
+        }
+    });
+    if validate_test.is_none() && snapshot_test.is_none() {
+        return None;
+    }
+    let mod_ident =
+        to_snake_case(&doc_struct.append(Ident::new("OpenapiTests", doc_struct.span())));
+    let scope_trait = Ident::new(&format!("__{doc_struct}OpenapiTests"), doc_struct.span());
+    // Tests cannot be run if the trait is declared in a function body, and
+    // the doc struct cannot be named from the tests module. The scope trait
+    // is only in scope in the tests module via `super::*` if the trait is
+    // declared in a module, otherwise `FnBodyScope` is used, which reports
+    // an error.
+    Some(quote! {
+        #[doc(hidden)]
+        trait #scope_trait {
+            fn #mod_ident(self) -> #crate_path::__utoipa::openapi::OpenApi;
+        }
+
+        impl #scope_trait for #crate_path::generated_tests::ModuleScope {
+            fn #mod_ident(self) -> #crate_path::__utoipa::openapi::OpenApi {
+                <#doc_struct as #crate_path::__utoipa::OpenApi>::openapi()
+            }
+        }
+
+        #[doc(hidden)]
+        mod #mod_ident {
+            use super::*;
+
+            trait FnBodyScope {
+                fn #mod_ident(self) -> #crate_path::__utoipa::openapi::OpenApi
+                where
+                    Self: #crate_path::generated_tests::InModule;
+            }
+
+            impl FnBodyScope for &#crate_path::generated_tests::ModuleScope {
+                fn #mod_ident(self) -> #crate_path::__utoipa::openapi::OpenApi
+                where
+                    Self: #crate_path::generated_tests::InModule,
+                {
+                    unreachable!()
+                }
+            }
+
+            #[cfg_attr(not(test), allow(dead_code))]
+            fn openapi() -> #crate_path::__utoipa::openapi::OpenApi {
+                #crate_path::generated_tests::ModuleScope.#mod_ident()
+            }
+
+            #validate_test
+
+            #snapshot_test
+        }
+    })
+}
+
 pub fn codegen(ir: Ir) -> Rust {
     let Ir {
         ref_schema_tys: _,
+        doc_struct: _,
+        doc_vis: _,
+        crate_path: _,
+        validate_test: _,
+        help: _,
+        request_enums: _,
+        named_schemas: _,
+        security_schemes: _,
+        extensions: _,
+        snapshot: _,
         methods: _,
+        doc_generics: _,
         ref item_trait,
     } = ir;
-    let doc_item = gen_doc(&ir);
+    // Only strip helper attributes if doc generation is disabled
+    let (doc_item, metadata, help, request_enums, named_schemas, tests) =
+        if cfg!(feature = "gen-doc") {
+            (
+                Some(gen_doc(&ir)),
+                Some(gen_metadata(&ir)),
+                gen_help(&ir),
+                gen_request_enums(&ir),
+                gen_named_schemas(&ir),
+                gen_tests(&ir),
+            )
+        } else {
+            (None, None, None, None, None, None)
+        };
     quote!(
         #item_trait
 
         #doc_item
+
+        #metadata
+
+        #help
+
+        #request_enums
+
+        #named_schemas
+
+        #tests
     )
 }
 
@@ -0,0 +1,470 @@
+//! Generate jsonrpsee client traits from OpenRPC documents
+
+use std::path::PathBuf;
+
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use serde_json::{Map, Value};
+use syn::{parse::Parser, spanned::Spanned, Ident, LitStr};
+
+pub struct Args {
+    /// Path to the document, relative to the crate root
+    path: LitStr,
+    /// Ident for the generated trait
+    trait_name: Option<Ident>,
+    /// Path to the `l2l_openapi` crate
+    crate_path: Option<syn::Path>,
+}
+
+impl syn::parse::Parse for Args {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let path: LitStr = input.parse()?;
+        let mut trait_name: Option<Ident> = None;
+        let mut crate_path: Option<syn::Path> = None;
+        if input.parse::<Option<syn::Token![,]>>()?.is_some() {
+            let args_parser =
+                syn::meta::parser(
+                    |meta| match meta.path.require_ident()?.to_string().as_str() {
+                        "crate" => {
+                            if crate_path.is_some() {
+                                let err_msg = "crate cannot be set more than once";
+                                return Err(meta.error(err_msg));
+                            }
+                            crate_path = Some(meta.value()?.parse::<LitStr>()?.parse()?);
+                            Ok(())
+                        }
+                        "trait_name" => {
+                            if trait_name.is_some() {
+                                let err_msg = "trait_name cannot be set more than once";
+                                return Err(meta.error(err_msg));
+                            }
+                            trait_name = Some(meta.value()?.parse::<LitStr>()?.parse()?);
+                            Ok(())
+                        }
+                        ident => {
+                            let err_msg = format!("unexpected key: {ident}");
+                            Err(syn::Error::new(meta.path.span(), err_msg))
+                        }
+                    },
+                );
+            let () = args_parser.parse2(input.parse()?)?;
+        }
+        Ok(Self {
+            path,
+            trait_name,
+            crate_path,
+        })
+    }
+}
+
+/// Convert a name to a valid Rust ident, replacing invalid characters with
+/// `_`
+fn sanitize(name: &str) -> String {
+    let res: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if res.is_empty() || res.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{res}")
+    } else {
+        res
+    }
+}
+
+/// Rust ident for a name, using a raw ident for keywords
+fn ident(name: &str) -> Ident {
+    let name = sanitize(name);
+    match syn::parse_str::<Ident>(&name) {
+        Ok(ident) => ident,
+        Err(_) if matches!(name.as_str(), "crate" | "self" | "Self" | "super") => {
+            format_ident!("{name}_")
+        }
+        Err(_) => Ident::new_raw(&name, Span::call_site()),
+    }
+}
+
+/// PascalCase Rust ident for a name
+fn pascal_ident(name: &str) -> Ident {
+    let name: String = name
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .flat_map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|c| c.to_ascii_uppercase())
+                .into_iter()
+                .chain(chars)
+        })
+        .collect();
+    ident(&name)
+}
+
+/// `#[doc]` attribute for a description
+fn doc_attr(description: Option<&Value>) -> Option<TokenStream> {
+    let description = description?.as_str()?;
+    Some(quote! { #[doc = #description] })
+}
+
+/// `#[serde(rename)]` attribute, if an ident differs from the wire name
+fn rename_attr(ident: &Ident, name: &str) -> Option<TokenStream> {
+    let ident_str = ident.to_string();
+    let ident_str = ident_str.strip_prefix("r#").unwrap_or(&ident_str);
+    (ident_str != name).then(|| quote! { #[serde(rename = #name)] })
+}
+
+/// Returns true if the schema is a null schema
+fn is_null(schema: &Value) -> bool {
+    schema.get("type") == Some(&Value::String("null".to_owned()))
+}
+
+/// Returns true if the schema is represented as an `Option`
+fn is_option(schema: &Value) -> bool {
+    if ["oneOf", "anyOf"].into_iter().any(|keyword| {
+        matches!(schema.get(keyword), Some(Value::Array(schemas))
+            if schemas.len() == 2 && schemas.iter().any(is_null))
+    }) {
+        return true;
+    }
+    matches!(schema.get("type"), Some(Value::Array(tys))
+        if tys.len() == 2 && tys.contains(&Value::String("null".to_owned())))
+}
+
+struct Codegen<'a> {
+    crate_path: &'a syn::Path,
+    /// Component schemas
+    components: &'a Map<String, Value>,
+}
+
+impl Codegen<'_> {
+    fn value_ty(&self) -> TokenStream {
+        let crate_path = self.crate_path;
+        quote! { #crate_path::__serde_json::Value }
+    }
+
+    fn integer_ty(schema: &Map<String, Value>) -> TokenStream {
+        let unsigned = schema
+            .get("minimum")
+            .and_then(Value::as_f64)
+            .is_some_and(|minimum| minimum >= 0.0);
+        let format = schema.get("format").and_then(Value::as_str);
+        match (format, unsigned) {
+            (Some("int8"), false) => quote! { i8 },
+            (Some("int8" | "uint8"), _) => quote! { u8 },
+            (Some("int16"), false) => quote! { i16 },
+            (Some("int16" | "uint16"), _) => quote! { u16 },
+            (Some("int32"), false) => quote! { i32 },
+            (Some("int32" | "uint32"), _) => quote! { u32 },
+            (Some("uint64"), _) | (_, true) => quote! { u64 },
+            (_, false) => quote! { i64 },
+        }
+    }
+
+    /// Rust type for a JSON type name
+    fn json_ty(&self, json_ty: &str, schema: &Map<String, Value>) -> TokenStream {
+        match json_ty {
+            "null" => quote! { () },
+            "boolean" => quote! { bool },
+            "integer" => Self::integer_ty(schema),
+            "number" => match schema.get("format").and_then(Value::as_str) {
+                Some("float") => quote! { f32 },
+                _ => quote! { f64 },
+            },
+            "string" => quote! { ::std::string::String },
+            "array" => match (schema.get("prefixItems"), schema.get("items")) {
+                (Some(Value::Array(prefix_items)), _) => {
+                    let tys = prefix_items.iter().map(|item| self.ty(item));
+                    quote! { (#(#tys,)*) }
+                }
+                (_, Some(items)) => {
+                    let item_ty = self.ty(items);
+                    quote! { ::std::vec::Vec<#item_ty> }
+                }
+                (_, None) => {
+                    let value_ty = self.value_ty();
+                    quote! { ::std::vec::Vec<#value_ty> }
+                }
+            },
+            "object" => match schema.get("additionalProperties") {
+                Some(additional) if !schema.contains_key("properties") => {
+                    let value_ty = self.ty(additional);
+                    quote! {
+                        ::std::collections::BTreeMap<::std::string::String, #value_ty>
+                    }
+                }
+                _ => self.value_ty(),
+            },
+            _ => self.value_ty(),
+        }
+    }
+
+    /// Rust type for a schema. Schemas that cannot be represented by a named
+    /// type are represented as JSON values.
+    fn ty(&self, schema: &Value) -> TokenStream {
+        // Documents inline the schemas of top-level types, so inline schemas
+        // that match a component schema are represented by the component
+        if let Some((name, _)) = self
+            .components
+            .iter()
+            .find(|(_, component)| *component == schema)
+        {
+            let ident = pascal_ident(name);
+            return quote! { #ident };
+        }
+        self.unnamed_ty(schema)
+    }
+
+    /// Rust type for a schema, without matching component schemas
+    fn unnamed_ty(&self, schema: &Value) -> TokenStream {
+        let schema = match schema {
+            Value::Object(schema) => schema,
+            _ => return self.value_ty(),
+        };
+        if let Some(Value::String(reference)) = schema.get("$ref") {
+            return match reference.strip_prefix("#/components/schemas/") {
+                Some(name) => {
+                    let ident = pascal_ident(name);
+                    quote! { #ident }
+                }
+                None => self.value_ty(),
+            };
+        }
+        // Unit types are documented with a `null` default and no constraints
+        if schema.len() == 1 && schema.get("default") == Some(&Value::Null) {
+            return quote! { () };
+        }
+        for keyword in ["oneOf", "anyOf"] {
+            if let Some(Value::Array(schemas)) = schema.get(keyword) {
+                return match schemas.as_slice() {
+                    [schema] => self.ty(schema),
+                    [lhs, rhs] if is_null(lhs) || is_null(rhs) => {
+                        let inner = if is_null(lhs) { rhs } else { lhs };
+                        let inner_ty = self.ty(inner);
+                        quote! { ::std::option::Option<#inner_ty> }
+                    }
+                    _ => self.value_ty(),
+                };
+            }
+        }
+        match schema.get("type") {
+            Some(Value::String(ty)) => self.json_ty(ty, schema),
+            Some(Value::Array(tys)) => {
+                let tys: Vec<&str> = tys.iter().filter_map(Value::as_str).collect();
+                match tys.as_slice() {
+                    [ty] => self.json_ty(ty, schema),
+                    [lhs, rhs] if *lhs == "null" || *rhs == "null" => {
+                        let inner = if *lhs == "null" { rhs } else { lhs };
+                        let inner_ty = self.json_ty(inner, schema);
+                        quote! { ::std::option::Option<#inner_ty> }
+                    }
+                    _ => self.value_ty(),
+                }
+            }
+            _ => self.value_ty(),
+        }
+    }
+
+    /// Rust type for a schema, wrapped in `Option` if it is not already
+    /// optional
+    fn optional_ty(&self, schema: &Value) -> TokenStream {
+        let ty = self.ty(schema);
+        if is_option(schema) {
+            ty
+        } else {
+            quote! { ::std::option::Option<#ty> }
+        }
+    }
+
+    /// Struct, enum or type alias for a component schema
+    fn component(&self, name: &str, schema: &Value) -> TokenStream {
+        let ty_ident = pascal_ident(name);
+        let doc = doc_attr(schema.get("description"));
+        let crate_path = self.crate_path;
+        let serde = quote! { #crate_path::__serde };
+        let serde_crate = serde.to_string().replace(' ', "");
+        let derives = quote! {
+            #[derive(Clone, Debug, #serde::Deserialize, #serde::Serialize)]
+            #[serde(crate = #serde_crate)]
+        };
+        let ty = schema.get("type").and_then(Value::as_str);
+        match (ty, schema.get("properties"), schema.get("enum")) {
+            (Some("object"), Some(Value::Object(properties)), _) => {
+                let required: Vec<&str> = match schema.get("required") {
+                    Some(Value::Array(required)) => {
+                        required.iter().filter_map(Value::as_str).collect()
+                    }
+                    _ => Vec::new(),
+                };
+                let fields = properties.iter().map(|(name, property)| {
+                    let field_ident = ident(name);
+                    let doc = doc_attr(property.get("description"));
+                    let rename = rename_attr(&field_ident, name);
+                    let (ty, skip) = if required.contains(&name.as_str()) {
+                        (self.ty(property), None)
+                    } else {
+                        let skip = quote! {
+                            #[serde(default, skip_serializing_if = "Option::is_none")]
+                        };
+                        (self.optional_ty(property), Some(skip))
+                    };
+                    quote! {
+                        #doc
+                        #rename
+                        #skip
+                        pub #field_ident: #ty,
+                    }
+                });
+                quote! {
+                    #doc
+                    #derives
+                    #[allow(non_snake_case)]
+                    pub struct #ty_ident {
+                        #(#fields)*
+                    }
+                }
+            }
+            (Some("string"), _, Some(Value::Array(values)))
+                if values.iter().all(Value::is_string) =>
+            {
+                let variants = values.iter().filter_map(Value::as_str).map(|value| {
+                    let variant_ident = pascal_ident(value);
+                    let rename = rename_attr(&variant_ident, value);
+                    quote! {
+                        #rename
+                        #variant_ident,
+                    }
+                });
+                quote! {
+                    #doc
+                    #derives
+                    pub enum #ty_ident {
+                        #(#variants)*
+                    }
+                }
+            }
+            _ => {
+                let ty = self.unnamed_ty(schema);
+                quote! {
+                    #doc
+                    pub type #ty_ident = #ty;
+                }
+            }
+        }
+    }
+
+    /// Client method for an OpenRPC method object
+    fn method(&self, method: &Value) -> syn::Result<TokenStream> {
+        let Some(name) = method.get("name").and_then(Value::as_str) else {
+            let err_msg = "method does not have a name";
+            return Err(syn::Error::new(Span::call_site(), err_msg));
+        };
+        let fn_ident = ident(name);
+        let doc = doc_attr(method.get("description").or_else(|| method.get("summary")));
+        let deprecated = (method.get("deprecated") == Some(&Value::Bool(true)))
+            .then(|| quote! { #[deprecated] });
+        let params = method
+            .get("params")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .map(|param| {
+                let param_name = param.get("name").and_then(Value::as_str).unwrap_or("param");
+                let param_ident = ident(param_name);
+                let schema = param.get("schema").unwrap_or(&Value::Bool(true));
+                let ty = if param.get("required") == Some(&Value::Bool(false)) {
+                    self.optional_ty(schema)
+                } else {
+                    self.ty(schema)
+                };
+                quote! { #param_ident: #ty }
+            });
+        let param_kind = (method.get("paramStructure") == Some(&Value::from("by-name")))
+            .then(|| quote! { , param_kind = map });
+        let result_ty = match method.pointer("/result/schema") {
+            Some(schema) => self.ty(schema),
+            None => self.value_ty(),
+        };
+        let crate_path = self.crate_path;
+        Ok(quote! {
+            #doc
+            #deprecated
+            #[allow(non_snake_case)]
+            #[method(name = #name #param_kind)]
+            async fn #fn_ident(
+                &self,
+                #(#params),*
+            ) -> #crate_path::__jsonrpsee::core::RpcResult<#result_ty>;
+        })
+    }
+}
+
+/// Default trait name, from the document title or file name, eg.
+/// `NodeDoc.openrpc.json` -> `Node`
+fn default_trait_name(doc: &Value, path: &str) -> String {
+    if let Some(title) = doc.pointer("/info/title").and_then(Value::as_str) {
+        if !title.is_empty() {
+            return title.to_owned();
+        }
+    }
+    let file_name = path.rsplit(['/', '\\']).next().unwrap_or(path);
+    let stem = file_name.split('.').next().unwrap_or(file_name);
+    stem.strip_suffix("Doc").unwrap_or(stem).to_owned()
+}
+
+pub fn import(args: Args) -> syn::Result<TokenStream> {
+    let Args {
+        path,
+        trait_name,
+        crate_path,
+    } = args;
+    let crate_path = crate_path.unwrap_or_else(|| syn::parse_quote!(::l2l_openapi));
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR")
+        .map_err(|err| syn::Error::new(path.span(), err.to_string()))?;
+    let full_path = PathBuf::from(manifest_dir).join(path.value());
+    let json = std::fs::read_to_string(&full_path).map_err(|err| {
+        let err_msg = format!("failed to read `{}`: {err}", full_path.display());
+        syn::Error::new(path.span(), err_msg)
+    })?;
+    let doc: Value = serde_json::from_str(&json).map_err(|err| {
+        let err_msg = format!("failed to parse `{}`: {err}", full_path.display());
+        syn::Error::new(path.span(), err_msg)
+    })?;
+    if doc.get("openrpc").is_none() {
+        let err_msg = "expected an OpenRPC document";
+        return Err(syn::Error::new(path.span(), err_msg));
+    }
+    let trait_ident =
+        trait_name.unwrap_or_else(|| pascal_ident(&default_trait_name(&doc, &path.value())));
+    let empty_components = Map::new();
+    let codegen = Codegen {
+        crate_path: &crate_path,
+        components: doc
+            .pointer("/components/schemas")
+            .and_then(Value::as_object)
+            .unwrap_or(&empty_components),
+    };
+    let components = codegen
+        .components
+        .iter()
+        .map(|(name, schema)| codegen.component(name, schema));
+    let methods = doc
+        .get("methods")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .map(|method| codegen.method(method))
+        .collect::<syn::Result<Vec<_>>>()?;
+    let full_path = full_path.to_string_lossy();
+    let trait_doc = doc_attr(doc.pointer("/info/description"));
+    Ok(quote! {
+        // Recompile if the document changes
+        const _: &[u8] = include_bytes!(#full_path);
+
+        #(#components)*
+
+        #trait_doc
+        #[::jsonrpsee::proc_macros::rpc(client)]
+        pub trait #trait_ident {
+            #(#methods)*
+        }
+    })
+}
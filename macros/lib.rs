@@ -2,9 +2,77 @@ use proc_macro::TokenStream;
 
 mod analyze;
 mod codegen;
+mod import;
 mod lower;
 mod parse;
 
+/// Generates a `{Trait}Doc` struct that implements `utoipa::OpenApi`, with a
+/// `METHODS` const that has runtime metadata (`l2l_openapi::MethodInfo`) for
+/// each method.
+///
+/// Arguments:
+/// * `ref_schemas [A, B]`: types to add to `components.schemas`
+/// * `doc_struct = "Name"`: ident of the generated struct
+/// * `doc_vis = "pub(crate)"`: visibility of the generated struct. Defaults to
+///   the visibility of the trait.
+/// * `crate = "path::to::l2l_openapi"`: path to the `l2l_openapi` crate
+/// * `validate_test`: generate a test that validates the generated doc with
+///   `l2l_openapi::validate`, in a `{doc_struct}_openapi_tests` module (in
+///   snake case). Not supported for traits declared in function bodies.
+/// * `help`: generate `help(method)` and `help_module()` functions on the doc
+///   struct. The module has a bitcoind-style `help` method, that returns a
+///   usage summary of every method, or the full text for a single method.
+/// * `request_enums`: generate `{Trait}Request` and `{Trait}Response` enums,
+///   with a variant for each method. Requests (de)serialize as
+///   `{"method": .., "params": ..}` objects, respecting `param_kind` and
+///   argument renames. Responses serialize as the result. Param and result
+///   types must implement `Serialize` and `Deserialize`.
+/// * `named_schemas`: generate a `{Method}Params` struct for each method with
+///   more than one param, and a `{Method}Result` type alias for each method
+///   with a result. Both are added to `components.schemas`, and referenced by
+///   the request body and response. Param types must implement `Serialize`
+///   and `Deserialize`.
+/// * `security_schemes(bearer = "bearer", basic = "basic")`: HTTP
+///   authentication schemes, by name, added to `components.securitySchemes`.
+///   Methods accept any of the schemes, unless `security` is set.
+/// * `extension(name = "x-internal", value = true)`: a vendor extension on the
+///   document. The value may be any JSON literal, eg. `5`, `"a"`, `null`,
+///   `[1, 2]` or `{"a": 1}`. May be repeated.
+/// * `snapshot = "openapi.json"`: generate a test that compares the generated
+///   doc with a file, relative to the crate root, using
+///   `l2l_openapi::check_snapshot`. Set `L2L_OPENAPI_BLESS=1` to update the
+///   file. The test is in the same module as the `validate_test` test, and is
+///   not supported for traits declared in function bodies.
+///
+/// Paths and operation IDs are the method names that jsonrpsee registers,
+/// including the `namespace` of the `#[rpc]` attribute, eg. `chain_getHeight`.
+/// Method aliases are listed in an `x-aliases` operation extension.
+/// Earlier versions used the Rust method name, so documents and clients that
+/// refer to operations by path or operation ID may need to be updated.
+///
+/// Methods can be annotated with `#[open_api_method(..)]`:
+/// * `output_schema(PartialSchema = "Ty")`, `output_schema(ToSchema = "Ty")`:
+///   override the result schema
+/// * `example(params = "[..]", result = "..")`: example positional params and
+///   result, as JSON. May be repeated.
+/// * `tags = ["a", "b"]`: operation tags
+/// * `deprecated`: mark the operation as deprecated
+/// * `audience = "admin"`: audience of the method, emitted as `x-audience`.
+///   Defaults to `l2l_openapi::DEFAULT_AUDIENCE`.
+///   `{Trait}Doc::openapi_for(&["public"])` returns a document with only the
+///   methods for the given audiences.
+/// * `since = "0.5.0"`, `removed_in = "0.8.0"`: versions that the method was
+///   added and removed in, emitted as `x-since` and `x-removed-in`, and noted
+///   in the description. `{Trait}Doc::openapi_at_version("0.6.0")` returns a
+///   document with only the methods that exist at a version.
+/// * `security = ["bearer"]`: security schemes that the method accepts, any of
+///   which may be used. `security = []` documents the method as
+///   unauthenticated.
+/// * `extension(name = "x-cost", value = 5)`: a vendor extension on the
+///   operation, as for the document. May be repeated.
+/// * `read_only`, `idempotent`, `mutating`: side-effect markers, emitted as
+///   `x-read-only`, `x-idempotent` and `x-mutating`. `read_only` and
+///   `mutating` cannot both be set.
 #[proc_macro_attribute]
 pub fn open_api(args: TokenStream, item: TokenStream) -> TokenStream {
     let ast = match parse::parse(args.into(), item.into()) {
@@ -19,3 +87,36 @@ pub fn open_api(args: TokenStream, item: TokenStream) -> TokenStream {
     let rust = codegen::codegen(ir);
     rust.into()
 }
+
+/// Generates a jsonrpsee client trait and serde types from an OpenRPC
+/// document, such as one written by `l2l_openapi::export`.
+///
+/// `l2l_openapi::import!("spec/NodeDoc.openrpc.json")` generates:
+/// * a struct, enum or type alias for each component schema
+/// * a `#[rpc(client)]` trait with a method for each documented method, so
+///   that jsonrpsee generates a `{Trait}Client` extension trait
+///
+/// The path is relative to the crate root. The crate must depend on
+/// `jsonrpsee` (with the `macros` feature and a client feature), since the
+/// code generated by `#[rpc]` refers to `jsonrpsee` directly. Other generated
+/// code uses the crate set with `crate`, so a `serde` dependency is not
+/// needed.
+///
+/// Arguments, after the path:
+/// * `trait_name = "Name"`: ident of the generated trait. Defaults to the
+///   document title, or the file name without a `Doc` suffix.
+/// * `crate = "path::to::l2l_openapi"`: path to the `l2l_openapi` crate
+///
+/// Schemas that cannot be represented as Rust types, such as inline objects,
+/// are represented as `serde_json::Value`.
+#[proc_macro]
+pub fn import(input: TokenStream) -> TokenStream {
+    let args = match syn::parse::<import::Args>(input) {
+        Ok(args) => args,
+        Err(err) => return err.into_compile_error().into(),
+    };
+    match import::import(args) {
+        Ok(rust) => rust.into(),
+        Err(err) => err.into_compile_error().into(),
+    }
+}
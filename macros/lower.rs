@@ -1,22 +1,98 @@
-use syn::ItemTrait;
+use proc_macro_roids::IdentExt;
+use syn::{Generics, Ident, ItemTrait};
 
-use crate::analyze::{Method, Model};
+use crate::{
+    analyze::{Method, Model},
+    parse::{Extension, SecurityScheme},
+};
 
 pub struct Ir {
     pub ref_schema_tys: Vec<syn::Type>,
+    /// Ident of the generated doc struct
+    pub doc_struct: Ident,
+    /// Visibility of the generated doc struct
+    pub doc_vis: syn::Visibility,
+    /// Path to the `l2l_openapi` crate
+    pub crate_path: syn::Path,
+    /// Generate a test that validates the generated doc
+    pub validate_test: bool,
+    /// Generate a `help` RPC module
+    pub help: bool,
+    /// Generate `{Trait}Request` and `{Trait}Response` enums
+    pub request_enums: bool,
+    /// Generate named component schemas for params and results
+    pub named_schemas: bool,
+    /// Security schemes, added to `components.securitySchemes`
+    pub security_schemes: Vec<SecurityScheme>,
+    /// Vendor extensions for the document
+    pub extensions: Vec<Extension>,
+    /// Path to a snapshot of the generated doc, relative to the crate root
+    pub snapshot: Option<syn::LitStr>,
     pub methods: Vec<Method>,
+    /// Generics for the generated doc struct
+    pub doc_generics: Generics,
     pub item_trait: ItemTrait,
 }
 
+/// Returns true if the type is `Self`
+fn is_self_ty(ty: &syn::Type) -> bool {
+    matches!(ty, syn::Type::Path(type_path)
+        if type_path.qself.is_none() && type_path.path.is_ident("Self"))
+}
+
+/// Generics for the doc struct are the trait generics
+fn doc_generics(item_trait: &ItemTrait) -> Generics {
+    let mut generics = item_trait.generics.clone();
+    // Predicates on `Self` do not apply to the doc struct
+    if let Some(where_clause) = &mut generics.where_clause {
+        where_clause.predicates = std::mem::take(&mut where_clause.predicates)
+            .into_iter()
+            .filter(|predicate| {
+                !matches!(predicate, syn::WherePredicate::Type(predicate_ty)
+                    if is_self_ty(&predicate_ty.bounded_ty))
+            })
+            .collect();
+    }
+    generics
+}
+
 pub fn lower(model: Model) -> Ir {
     let Model {
         ref_schema_tys,
+        doc_struct,
+        doc_vis,
+        crate_path,
+        validate_test,
+        help,
+        request_enums,
+        named_schemas,
+        security_schemes,
+        extensions,
+        snapshot,
         methods,
         item_trait,
     } = model;
+    let doc_struct = doc_struct.unwrap_or_else(|| {
+        let ident = &item_trait.ident;
+        ident.append(Ident::new("Doc", ident.span()))
+    });
+    let doc_vis = doc_vis.unwrap_or_else(|| item_trait.vis.clone());
+    let crate_path = crate_path.unwrap_or_else(|| syn::parse_quote!(::l2l_openapi));
+    let doc_generics = doc_generics(&item_trait);
     Ir {
         ref_schema_tys,
+        doc_struct,
+        doc_vis,
+        crate_path,
+        validate_test,
+        help,
+        request_enums,
+        named_schemas,
+        security_schemes,
+        extensions,
+        snapshot,
         methods,
+        doc_generics,
         item_trait,
     }
 }
@@ -1,17 +1,263 @@
 use proc_macro2::TokenStream;
 use syn::{parse::Parser, punctuated::Punctuated, spanned::Spanned, Item, ItemTrait};
 
+/// A security scheme, set with `security_schemes(name = "scheme")`
+pub struct SecurityScheme {
+    /// Name of the scheme in `components.securitySchemes`
+    pub name: syn::Ident,
+    /// Variant of `utoipa::openapi::security::HttpAuthScheme`
+    pub http_auth_scheme: syn::Ident,
+}
+
+/// HTTP authentication schemes, and the corresponding variants of
+/// `utoipa::openapi::security::HttpAuthScheme`
+const HTTP_AUTH_SCHEMES: &[(&str, &str)] = &[
+    ("basic", "Basic"),
+    ("bearer", "Bearer"),
+    ("digest", "Digest"),
+    ("hoba", "Hoba"),
+    ("mutual", "Mutual"),
+    ("negotiate", "Negotiate"),
+    ("oauth", "OAuth"),
+    ("scram-sha-1", "ScramSha1"),
+    ("scram-sha-256", "ScramSha256"),
+    ("vapid", "Vapid"),
+];
+
+/// Extensions that are generated by `#[open_api]`, and cannot be set with
+/// `extension(..)`
+const GENERATED_EXTENSIONS: &[&str] = &[
+    "x-aliases",
+    "x-audience",
+    "x-idempotent",
+    "x-mutating",
+    "x-param-names",
+    "x-param-structure",
+    "x-read-only",
+    "x-removed-in",
+    "x-since",
+];
+
+/// A vendor extension, set with `extension(name = "x-..", value = ..)`
+#[derive(Debug)]
+pub struct Extension {
+    pub name: syn::LitStr,
+    /// Value, as JSON
+    pub value: String,
+}
+
+/// A JSON literal, eg. `5`, `-1.5`, `"a"`, `true`, `null`, `[1, 2]` or
+/// `{"a": 1}`
+struct JsonLit(serde_json::Value);
+
+impl syn::parse::Parse for JsonLit {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let lookahead = input.lookahead1();
+        if lookahead.peek(syn::token::Bracket) {
+            let items;
+            syn::bracketed!(items in input);
+            let items = Punctuated::<Self, syn::token::Comma>::parse_terminated(&items)?;
+            Ok(Self(serde_json::Value::Array(
+                items.into_iter().map(|item| item.0).collect(),
+            )))
+        } else if lookahead.peek(syn::token::Brace) {
+            let members;
+            syn::braced!(members in input);
+            let members = Punctuated::<(String, Self), syn::token::Comma>::parse_terminated_with(
+                &members,
+                |input| {
+                    let key: syn::LitStr = input.parse()?;
+                    let _: syn::Token![:] = input.parse()?;
+                    Ok((key.value(), input.parse()?))
+                },
+            )?;
+            Ok(Self(serde_json::Value::Object(
+                members
+                    .into_iter()
+                    .map(|(key, value)| (key, value.0))
+                    .collect(),
+            )))
+        } else if lookahead.peek(syn::Token![-]) {
+            let _: syn::Token![-] = input.parse()?;
+            let lit: syn::Lit = input.parse()?;
+            let value = match &lit {
+                syn::Lit::Int(lit_int) => lit_int
+                    .base10_parse::<i64>()
+                    .map(|value| -value)
+                    .map(serde_json::Value::from),
+                syn::Lit::Float(lit_float) => lit_float
+                    .base10_parse::<f64>()
+                    .map(|value| serde_json::Value::from(-value)),
+                _ => Err(syn::Error::new(lit.span(), "expected a number")),
+            }?;
+            Ok(Self(value))
+        } else if lookahead.peek(syn::Lit) {
+            let lit: syn::Lit = input.parse()?;
+            let value = match &lit {
+                syn::Lit::Str(lit_str) => serde_json::Value::String(lit_str.value()),
+                syn::Lit::Int(lit_int) => {
+                    lit_int.base10_parse::<u64>().map(serde_json::Value::from)?
+                }
+                syn::Lit::Float(lit_float) => lit_float
+                    .base10_parse::<f64>()
+                    .map(serde_json::Value::from)?,
+                syn::Lit::Bool(lit_bool) => serde_json::Value::Bool(lit_bool.value),
+                _ => return Err(syn::Error::new(lit.span(), "expected a JSON literal")),
+            };
+            Ok(Self(value))
+        } else if input.peek(syn::Ident) {
+            let ident: syn::Ident = input.parse()?;
+            if ident == "null" {
+                Ok(Self(serde_json::Value::Null))
+            } else {
+                Err(syn::Error::new(ident.span(), "expected a JSON literal"))
+            }
+        } else {
+            Err(lookahead.error())
+        }
+    }
+}
+
+/// Parse the arguments of `extension(name = "x-..", value = ..)`, and add the
+/// extension to a list of extensions
+pub fn parse_extension(
+    meta: &syn::meta::ParseNestedMeta,
+    extensions: &mut Vec<Extension>,
+) -> syn::Result<()> {
+    let mut name: Option<syn::LitStr> = None;
+    let mut value: Option<JsonLit> = None;
+    meta.parse_nested_meta(
+        |meta| match meta.path.require_ident()?.to_string().as_str() {
+            "name" => {
+                if name.is_some() {
+                    return Err(meta.error("name cannot be set more than once"));
+                }
+                name = Some(meta.value()?.parse()?);
+                Ok(())
+            }
+            "value" => {
+                if value.is_some() {
+                    return Err(meta.error("value cannot be set more than once"));
+                }
+                value = Some(meta.value()?.parse()?);
+                Ok(())
+            }
+            ident => {
+                let err_msg = format!("unexpected key: {ident}");
+                Err(syn::Error::new(meta.path.span(), err_msg))
+            }
+        },
+    )?;
+    let (Some(name), Some(value)) = (name, value) else {
+        return Err(meta.error("extension requires a name and a value"));
+    };
+    let name_str = name.value();
+    if !name_str.starts_with("x-") {
+        return Err(syn::Error::new(
+            name.span(),
+            "extension names must start with `x-`",
+        ));
+    }
+    if GENERATED_EXTENSIONS.contains(&name_str.as_str()) {
+        let err_msg = format!("`{name_str}` is generated, and cannot be set");
+        return Err(syn::Error::new(name.span(), err_msg));
+    }
+    if extensions
+        .iter()
+        .any(|extension| extension.name.value() == name_str)
+    {
+        let err_msg = format!("extension `{name_str}` is set more than once");
+        return Err(syn::Error::new(name.span(), err_msg));
+    }
+    extensions.push(Extension {
+        name,
+        value: value.0.to_string(),
+    });
+    Ok(())
+}
+
 pub struct Ast {
     /// Reference schema types
     pub ref_schema_tys: Option<Punctuated<syn::Type, syn::token::Comma>>,
+    /// Ident for the generated doc struct
+    pub doc_struct: Option<syn::Ident>,
+    /// Visibility for the generated doc struct
+    pub doc_vis: Option<syn::Visibility>,
+    /// Path to the `l2l_openapi` crate
+    pub crate_path: Option<syn::Path>,
+    /// Generate a test that validates the generated doc
+    pub validate_test: bool,
+    /// Generate a `help` RPC module
+    pub help: bool,
+    /// Generate `{Trait}Request` and `{Trait}Response` enums
+    pub request_enums: bool,
+    /// Generate named component schemas for params and results
+    pub named_schemas: bool,
+    /// Security schemes, if set
+    pub security_schemes: Option<Vec<SecurityScheme>>,
+    /// Extensions for the document
+    pub extensions: Vec<Extension>,
+    /// Path to a snapshot of the generated doc, relative to the crate root
+    pub snapshot: Option<syn::LitStr>,
     pub item_trait: ItemTrait,
 }
 
 pub fn parse(args: TokenStream, item: TokenStream) -> syn::Result<Ast> {
     let mut ref_schema_tys: Option<Punctuated<syn::Type, syn::token::Comma>> = None;
+    let mut doc_struct: Option<syn::Ident> = None;
+    let mut doc_vis: Option<syn::Visibility> = None;
+    let mut crate_path: Option<syn::Path> = None;
+    let mut validate_test = false;
+    let mut help = false;
+    let mut request_enums = false;
+    let mut named_schemas = false;
+    let mut security_schemes: Option<Vec<SecurityScheme>> = None;
+    let mut extensions = Vec::new();
+    let mut snapshot: Option<syn::LitStr> = None;
     let args_parser =
         syn::meta::parser(
             |meta| match meta.path.require_ident()?.to_string().as_str() {
+                "crate" => {
+                    if crate_path.is_some() {
+                        let err_msg = "crate cannot be set more than once";
+                        return Err(meta.error(err_msg));
+                    }
+                    crate_path = Some(meta.value()?.parse::<syn::LitStr>()?.parse()?);
+                    Ok(())
+                }
+                "doc_struct" => {
+                    if doc_struct.is_some() {
+                        let err_msg = "doc_struct cannot be set more than once";
+                        return Err(meta.error(err_msg));
+                    }
+                    doc_struct = Some(meta.value()?.parse::<syn::LitStr>()?.parse()?);
+                    Ok(())
+                }
+                "doc_vis" => {
+                    if doc_vis.is_some() {
+                        let err_msg = "doc_vis cannot be set more than once";
+                        return Err(meta.error(err_msg));
+                    }
+                    doc_vis = Some(meta.value()?.parse::<syn::LitStr>()?.parse()?);
+                    Ok(())
+                }
+                "extension" => parse_extension(&meta, &mut extensions),
+                "help" => {
+                    if help {
+                        let err_msg = "help cannot be set more than once";
+                        return Err(meta.error(err_msg));
+                    }
+                    help = true;
+                    Ok(())
+                }
+                "named_schemas" => {
+                    if named_schemas {
+                        let err_msg = "named_schemas cannot be set more than once";
+                        return Err(meta.error(err_msg));
+                    }
+                    named_schemas = true;
+                    Ok(())
+                }
                 "ref_schemas" => {
                     if ref_schema_tys.is_some() {
                         let err_msg = "ref_schemas cannot be set more than once";
@@ -26,6 +272,67 @@ pub fn parse(args: TokenStream, item: TokenStream) -> syn::Result<Ast> {
                     );
                     Ok(())
                 }
+                "request_enums" => {
+                    if request_enums {
+                        let err_msg = "request_enums cannot be set more than once";
+                        return Err(meta.error(err_msg));
+                    }
+                    request_enums = true;
+                    Ok(())
+                }
+                "security_schemes" => {
+                    if security_schemes.is_some() {
+                        let err_msg = "security_schemes cannot be set more than once";
+                        return Err(meta.error(err_msg));
+                    }
+                    let mut schemes: Vec<SecurityScheme> = Vec::new();
+                    meta.parse_nested_meta(|meta| {
+                        let name = meta.path.require_ident()?.clone();
+                        if schemes.iter().any(|scheme| scheme.name == name) {
+                            let err_msg = format!("security scheme `{name}` is set more than once");
+                            return Err(meta.error(err_msg));
+                        }
+                        let scheme: syn::LitStr = meta.value()?.parse()?;
+                        let Some((_, variant)) =
+                            HTTP_AUTH_SCHEMES.iter().find(|(http_scheme, _)| {
+                                http_scheme.eq_ignore_ascii_case(&scheme.value())
+                            })
+                        else {
+                            let http_schemes: Vec<_> = HTTP_AUTH_SCHEMES
+                                .iter()
+                                .map(|(http_scheme, _)| *http_scheme)
+                                .collect();
+                            let err_msg = format!(
+                                "unknown HTTP authentication scheme; expected one of: {}",
+                                http_schemes.join(", ")
+                            );
+                            return Err(syn::Error::new(scheme.span(), err_msg));
+                        };
+                        schemes.push(SecurityScheme {
+                            name,
+                            http_auth_scheme: syn::Ident::new(variant, scheme.span()),
+                        });
+                        Ok(())
+                    })?;
+                    security_schemes = Some(schemes);
+                    Ok(())
+                }
+                "snapshot" => {
+                    if snapshot.is_some() {
+                        let err_msg = "snapshot cannot be set more than once";
+                        return Err(meta.error(err_msg));
+                    }
+                    snapshot = Some(meta.value()?.parse()?);
+                    Ok(())
+                }
+                "validate_test" => {
+                    if validate_test {
+                        let err_msg = "validate_test cannot be set more than once";
+                        return Err(meta.error(err_msg));
+                    }
+                    validate_test = true;
+                    Ok(())
+                }
                 ident => {
                     let err_msg = format!("unexpected key: {ident}");
                     Err(syn::Error::new(meta.path.span(), err_msg))
@@ -35,10 +342,39 @@ pub fn parse(args: TokenStream, item: TokenStream) -> syn::Result<Ast> {
     let () = args_parser.parse2(args)?;
 
     match syn::parse2::<Item>(item) {
-        Ok(Item::Trait(item_trait)) => Ok(Ast {
-            ref_schema_tys,
-            item_trait,
-        }),
+        Ok(Item::Trait(item_trait)) => {
+            let is_generic = !item_trait.generics.params.is_empty();
+            if validate_test && is_generic {
+                let err_msg = "validate_test cannot be used with generic traits";
+                return Err(syn::Error::new(item_trait.ident.span(), err_msg));
+            }
+            if snapshot.is_some() && is_generic {
+                let err_msg = "snapshot cannot be used with generic traits";
+                return Err(syn::Error::new(item_trait.ident.span(), err_msg));
+            }
+            if request_enums && is_generic {
+                let err_msg = "request_enums cannot be used with generic traits";
+                return Err(syn::Error::new(item_trait.ident.span(), err_msg));
+            }
+            if named_schemas && is_generic {
+                let err_msg = "named_schemas cannot be used with generic traits";
+                return Err(syn::Error::new(item_trait.ident.span(), err_msg));
+            }
+            Ok(Ast {
+                ref_schema_tys,
+                doc_struct,
+                doc_vis,
+                crate_path,
+                validate_test,
+                help,
+                request_enums,
+                named_schemas,
+                security_schemes,
+                extensions,
+                snapshot,
+                item_trait,
+            })
+        }
         Ok(_item) => {
             // ../tests/ui/item-is-not-a-function.rs
             panic!("item is not a trait definition")